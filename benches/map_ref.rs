@@ -0,0 +1,55 @@
+//! Compares `get().map(f)` with the fused `map_ref(f)`.
+//!
+//! Run with `cargo bench --bench map_ref`. This is a plain timing loop, as no bench framework is available.
+//! The cases are measured in alternating rounds and the best round is reported,
+//! since the first loop of a round tends to run faster regardless of its body.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use option_cell::OptionCell;
+
+const ITERATIONS: u32 = 50_000_000;
+const ROUNDS: usize = 5;
+
+/// Returns the time per iteration of `f` in nanoseconds.
+fn time<F>(mut f: F) -> f64
+where
+    F: FnMut() -> usize,
+{
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(f());
+    }
+    start.elapsed().as_secs_f64() * 1e9 / f64::from(ITERATIONS)
+}
+
+fn main() {
+    let populated = OptionCell::from(Some(42usize));
+    let empty = OptionCell::<usize>::new();
+
+    for (label, cell) in [("populated", &populated), ("empty", &empty)] {
+        let get_map = || black_box(cell).get().map(|&x| x + 1).unwrap_or(0);
+        let map_ref = || black_box(cell).map_ref(|&x| x + 1).unwrap_or(0);
+
+        let mut best = [f64::INFINITY; 2];
+        for round in 0..ROUNDS {
+            // Alternate the order so that neither case always runs first.
+            let (first, second) = if round % 2 == 0 { (0, 1) } else { (1, 0) };
+            for i in [first, second] {
+                let ns = if i == 0 { time(get_map) } else { time(map_ref) };
+                best[i] = best[i].min(ns);
+            }
+        }
+        println!(
+            "{:<24} {:>8.3} ns/iter",
+            format!("get().map ({})", label),
+            best[0]
+        );
+        println!(
+            "{:<24} {:>8.3} ns/iter",
+            format!("map_ref ({})", label),
+            best[1]
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use std::ops::{Deref, DerefMut};
+
+use crate::OptionCell;
+
+/// An owned, growable collection of [OptionCell]s.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellVec<T>(Vec<OptionCell<T>>);
+
+impl<T> CellVec<T> {
+    /// Creates a new empty collection.
+    pub const fn new() -> Self {
+        CellVec(Vec::new())
+    }
+
+    /// Consumes the collection, returning the underlying vector of cells.
+    pub fn into_inner(self) -> Vec<OptionCell<T>> {
+        self.0
+    }
+}
+
+impl<T> Default for CellVec<T> {
+    fn default() -> Self {
+        CellVec::new()
+    }
+}
+
+impl<T> From<Vec<OptionCell<T>>> for CellVec<T> {
+    fn from(vec: Vec<OptionCell<T>>) -> Self {
+        CellVec(vec)
+    }
+}
+
+impl<T> From<CellVec<T>> for Vec<OptionCell<T>> {
+    fn from(vec: CellVec<T>) -> Self {
+        vec.into_inner()
+    }
+}
+
+impl<T> Deref for CellVec<T> {
+    type Target = [OptionCell<T>];
+
+    fn deref(&self) -> &[OptionCell<T>] {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for CellVec<T> {
+    fn deref_mut(&mut self) -> &mut [OptionCell<T>] {
+        &mut self.0
+    }
+}
+
+/// Pushes a populated cell for each value.
+impl<T> Extend<T> for CellVec<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.0
+            .extend(iter.into_iter().map(|value| OptionCell::from(Some(value))));
+    }
+}
+
+/// Pushes a cell for each option, populated or not.
+impl<T> Extend<Option<T>> for CellVec<T> {
+    fn extend<I: IntoIterator<Item = Option<T>>>(&mut self, iter: I) {
+        self.0.extend(iter.into_iter().map(OptionCell::from));
+    }
+}
+
+impl<T> FromIterator<T> for CellVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut vec = CellVec::new();
+        vec.extend(iter);
+        vec
+    }
+}
+
+impl<T> FromIterator<Option<T>> for CellVec<T> {
+    fn from_iter<I: IntoIterator<Item = Option<T>>>(iter: I) -> Self {
+        let mut vec = CellVec::new();
+        vec.extend(iter);
+        vec
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extend_values() {
+        let mut cells = CellVec::new();
+        cells.extend([1, 2]);
+        cells.extend(vec![3]);
+        assert_eq!(cells.len(), 3);
+        assert_eq!(cells[0].get(), Some(&1));
+        assert_eq!(cells[2].get(), Some(&3));
+    }
+
+    #[test]
+    fn test_extend_options() {
+        let mut cells: CellVec<i32> = [Some(1), None].into_iter().collect();
+        cells.extend([None, Some(4)]);
+        assert_eq!(cells.len(), 4);
+        assert_eq!(cells[1].get(), None);
+        assert_eq!(cells[3].get(), Some(&4));
+    }
+
+    #[test]
+    fn test_deref() {
+        let mut cells: CellVec<i32> = [Some(1), None, Some(3)].into_iter().collect();
+        let slice: &[OptionCell<i32>] = &cells;
+        assert!(slice[1].set(2).is_ok());
+        assert_eq!(cells.iter().filter(|cell| cell.get().is_some()).count(), 3);
+
+        let (left, _) = OptionCell::split_cells_mut(&mut cells, 1);
+        assert_eq!(left[0].take(), Some(1));
+        assert_eq!(
+            OptionCell::into_vec(cells.into_inner()),
+            vec![None, Some(2), Some(3)]
+        );
+    }
+}
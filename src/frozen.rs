@@ -0,0 +1,62 @@
+use crate::OptionCell;
+
+/// A definitely initialized cell, obtained by [OptionCell::try_freeze].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FrozenCell<T> {
+    value: T,
+}
+
+impl<T> FrozenCell<T> {
+    /// Gets the reference to the underlying value.
+    pub fn get(&self) -> &T {
+        &self.value
+    }
+
+    /// Consumes the cell, returning the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> OptionCell<T> {
+    /// Converts the cell into a [FrozenCell] if it is populated.
+    /// Otherwise, the empty cell is given back.
+    pub fn try_freeze(self) -> Result<FrozenCell<T>, Self> {
+        match self.into_inner() {
+            Some(value) => Ok(FrozenCell { value }),
+            None => Err(OptionCell::new()),
+        }
+    }
+}
+
+impl<T> From<FrozenCell<T>> for OptionCell<T> {
+    fn from(cell: FrozenCell<T>) -> Self {
+        OptionCell::from(Some(cell.into_inner()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_freeze() {
+        let cell = OptionCell::<i32>::new();
+        cell.set(42).unwrap();
+        let frozen = cell.try_freeze().unwrap();
+        assert_eq!(*frozen.get(), 42);
+        assert_eq!(frozen.into_inner(), 42);
+    }
+
+    #[test]
+    fn test_try_freeze_empty() {
+        let cell = OptionCell::<i32>::new();
+        let cell = cell.try_freeze().unwrap_err();
+        assert_eq!(cell.get(), None);
+        assert!(cell.set(42).is_ok());
+        assert_eq!(
+            OptionCell::from(cell.try_freeze().unwrap()).get(),
+            Some(&42)
+        );
+    }
+}
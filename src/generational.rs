@@ -0,0 +1,99 @@
+use crate::OptionCell;
+
+/// An [OptionCell] tagged with a generation, rejecting writes intended for other generations.
+///
+/// Bumping the generation empties the cell, so that the cell can be reused for the next generation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenOptionCell<T> {
+    generation: u32,
+    cell: OptionCell<T>,
+}
+
+impl<T> GenOptionCell<T> {
+    /// Creates a new empty cell at generation 0.
+    pub const fn new() -> Self {
+        Self {
+            generation: 0,
+            cell: OptionCell::new(),
+        }
+    }
+
+    /// Returns the current generation.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Gets the reference to the underlying value.
+    /// Returns `None` if the cell is empty.
+    pub fn get(&self) -> Option<&T> {
+        self.cell.get()
+    }
+
+    /// Sets the contents of this cell to `value` if `generation` is the current generation.
+    ///
+    /// The value is given back if the generation is stale or the cell is already populated.
+    pub fn set_gen(&self, generation: u32, value: T) -> Result<(), T> {
+        if generation == self.generation {
+            self.cell.set(value)
+        } else {
+            Err(value)
+        }
+    }
+
+    /// Advances to the next generation, emptying the cell.
+    /// Returns the new generation.
+    ///
+    /// The generation wraps around on overflow.
+    pub fn bump_generation(&mut self) -> u32 {
+        self.generation = self.generation.wrapping_add(1);
+        self.cell.take();
+        self.generation
+    }
+
+    /// Consumes the cell, returning the current generation and the wrapped `Option<T>`.
+    pub fn into_inner(self) -> (u32, Option<T>) {
+        (self.generation, self.cell.into_inner())
+    }
+}
+
+impl<T> Default for GenOptionCell<T> {
+    fn default() -> Self {
+        GenOptionCell::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_gen() {
+        let cell = GenOptionCell::<i32>::new();
+        assert_eq!(cell.generation(), 0);
+        assert_eq!(cell.set_gen(0, 42), Ok(()));
+        assert_eq!(cell.set_gen(0, 43), Err(43));
+        assert_eq!(cell.get(), Some(&42));
+    }
+
+    #[test]
+    fn test_set_gen_stale() {
+        let mut cell = GenOptionCell::<i32>::new();
+        cell.set_gen(0, 42).unwrap();
+        assert_eq!(cell.bump_generation(), 1);
+        assert_eq!(cell.get(), None);
+        assert_eq!(cell.set_gen(0, 43), Err(43));
+        assert_eq!(cell.set_gen(2, 44), Err(44));
+        assert_eq!(cell.get(), None);
+        assert_eq!(cell.set_gen(1, 45), Ok(()));
+        assert_eq!(cell.into_inner(), (1, Some(45)));
+    }
+
+    #[test]
+    fn test_default() {
+        struct NoDefault;
+
+        let cell = GenOptionCell::<NoDefault>::default();
+        assert_eq!(cell.generation(), 0);
+        assert!(cell.get().is_none());
+    }
+}
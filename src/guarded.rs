@@ -0,0 +1,148 @@
+use std::cell::Cell;
+
+use crate::{OptionCell, OptionCellError};
+
+/// An [OptionCell] paired with a busy flag, detecting reentrant initialization without a thread-local.
+///
+/// Unlike OptionCell, the layout is not compatible with `Option<T>`.
+#[derive(Debug)]
+pub struct GuardedOptionCell<T> {
+    // Invariant: set only while an initializer passed to `get_or_init` runs.
+    busy: Cell<bool>,
+    cell: OptionCell<T>,
+}
+
+impl<T> GuardedOptionCell<T> {
+    /// Creates a new empty cell.
+    pub const fn new() -> Self {
+        Self {
+            busy: Cell::new(false),
+            cell: OptionCell::new(),
+        }
+    }
+
+    /// Gets the reference to the underlying value.
+    /// Returns `None` if the cell is empty.
+    pub fn get(&self) -> Option<&T> {
+        self.cell.get()
+    }
+
+    /// Sets the contents of this cell to `value`.
+    pub fn set(&self, value: T) -> Result<(), T> {
+        self.cell.set(value)
+    }
+
+    /// Gets the contents of the cell, initializing with `f` if the cell was empty.
+    ///
+    /// Returns [OptionCellError::Recursion] if `f` tries to initialize the same cell,
+    /// or if `f` populates the cell by other means, e.g. via [GuardedOptionCell::set].
+    /// In the latter case the value returned by `f` is dropped before returning,
+    /// while the cell holds the value set from within `f`.
+    /// If `f` panics, the cell is left empty and can be initialized again.
+    pub fn get_or_init<F>(&self, f: F) -> Result<&T, OptionCellError>
+    where
+        F: FnOnce() -> T,
+    {
+        if let Some(value) = self.cell.get() {
+            return Ok(value);
+        }
+        if self.busy.replace(true) {
+            return Err(OptionCellError::Recursion);
+        }
+        let value = {
+            let _busy = BusyReset(&self.busy);
+            f()
+        };
+        // Reentrant initialization was rejected above, but `f` may still have populated the cell directly.
+        self.cell.set_and_get(value).map_err(|rejected| {
+            drop(rejected);
+            OptionCellError::Recursion
+        })
+    }
+
+    /// Returns `true` if an initializer is running.
+    pub fn is_busy(&self) -> bool {
+        self.busy.get()
+    }
+
+    /// Consumes the cell, returning the wrapped `Option<T>`.
+    pub fn into_inner(self) -> Option<T> {
+        self.cell.into_inner()
+    }
+}
+
+impl<T> Default for GuardedOptionCell<T> {
+    fn default() -> Self {
+        GuardedOptionCell::new()
+    }
+}
+
+/// Clears the busy flag when dropped, including on unwinding.
+struct BusyReset<'a>(&'a Cell<bool>);
+
+impl Drop for BusyReset<'_> {
+    fn drop(&mut self) {
+        self.0.set(false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_or_init() {
+        let cell = GuardedOptionCell::<i32>::new();
+        assert_eq!(cell.get(), None);
+        assert_eq!(cell.get_or_init(|| 42), Ok(&42));
+        assert_eq!(cell.get_or_init(|| panic!("must not be called")), Ok(&42));
+        assert!(!cell.is_busy());
+        assert_eq!(cell.into_inner(), Some(42));
+    }
+
+    #[test]
+    fn test_get_or_init_reentrant() {
+        let cell = GuardedOptionCell::<i32>::new();
+        let value = cell.get_or_init(|| {
+            assert!(cell.is_busy());
+            assert_eq!(cell.get_or_init(|| 43), Err(OptionCellError::Recursion));
+            42
+        });
+        assert_eq!(value, Ok(&42));
+        assert!(!cell.is_busy());
+    }
+
+    #[test]
+    fn test_get_or_init_set_within() {
+        let cell = GuardedOptionCell::<i32>::new();
+        let value = cell.get_or_init(|| {
+            cell.set(1).unwrap();
+            2
+        });
+        assert_eq!(value, Err(OptionCellError::Recursion));
+        assert!(!cell.is_busy());
+        assert_eq!(cell.get(), Some(&1));
+    }
+
+    #[test]
+    fn test_get_or_init_panic() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let cell = GuardedOptionCell::<i32>::new();
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            cell.get_or_init(|| panic!("init failed")).map(|_| ())
+        }));
+        assert!(result.is_err());
+        assert!(!cell.is_busy());
+        assert_eq!(cell.get_or_init(|| 42), Ok(&42));
+    }
+
+    #[test]
+    fn test_default() {
+        struct NoDefault;
+
+        let cell = GuardedOptionCell::<NoDefault>::default();
+        assert!(cell.get().is_none());
+        assert!(!cell.is_busy());
+    }
+}
@@ -20,8 +20,73 @@
 //! cells[0].set(1).unwrap();
 //! ```
 
-use std::cell::UnsafeCell;
+use std::cell::{RefCell, UnsafeCell};
+use std::cmp::Ordering;
 use std::fmt;
+#[cfg(feature = "async")]
+use std::future::Future;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::pin::Pin;
+use std::ptr::NonNull;
+use std::rc::{Rc, Weak};
+
+pub use cell_vec::CellVec;
+pub use frozen::FrozenCell;
+pub use generational::GenOptionCell;
+pub use guarded::GuardedOptionCell;
+
+pub mod locked;
+#[cfg(feature = "trace")]
+pub mod trace;
+
+mod cell_vec;
+mod frozen;
+mod generational;
+mod guarded;
+
+/// Asserts that the contents of an [OptionCell] is equal to the expected `Option`.
+///
+/// On failure, the macro panics with the actual contents of the cell.
+///
+/// ```rust
+/// use option_cell::{assert_cell_eq, OptionCell};
+///
+/// let cell = OptionCell::new();
+/// assert_cell_eq!(cell, None);
+/// cell.set(42).unwrap();
+/// assert_cell_eq!(cell, Some(42));
+/// ```
+#[macro_export]
+macro_rules! assert_cell_eq {
+    ($cell:expr, $expected:expr $(,)?) => {
+        match (&$cell, &$expected) {
+            (cell, expected) => {
+                if cell.get() != ::std::option::Option::as_ref(expected) {
+                    ::std::panic!(
+                        "assertion `cell == expected` failed\n    cell: {:?}\nexpected: {:?}",
+                        cell,
+                        expected,
+                    );
+                }
+            }
+        }
+    };
+    ($cell:expr, $expected:expr, $($arg:tt)+) => {
+        match (&$cell, &$expected) {
+            (cell, expected) => {
+                if cell.get() != ::std::option::Option::as_ref(expected) {
+                    ::std::panic!(
+                        "assertion `cell == expected` failed: {}\n    cell: {:?}\nexpected: {:?}",
+                        ::std::format_args!($($arg)+),
+                        cell,
+                        expected,
+                    );
+                }
+            }
+        }
+    };
+}
 
 /// An equivalent of [std::cell::OnceCell](https://doc.rust-lang.org/stable/std/cell/struct.OnceCell.html) or [once_cell::unsync::OnceCell](https://docs.rs/once_cell/latest/once_cell/unsync/struct.OnceCell.html)
 /// with an additional transmute helper.
@@ -70,6 +135,14 @@ impl<T> OptionCell<T> {
         }
     }
 
+    /// Sets the contents of this cell to `value` and returns the cell, for fluent construction.
+    ///
+    /// If the cell was already populated, `value` is dropped and the existing contents are kept.
+    pub fn with_set(self, value: T) -> Self {
+        let _ = self.set(value);
+        self
+    }
+
     /// Gets the reference to the underlying value.
     /// Returns `None` if the cell is empty.
     pub fn get(&self) -> Option<&T> {
@@ -84,6 +157,278 @@ impl<T> OptionCell<T> {
         unsafe { &*self.inner.get() }.as_ref()
     }
 
+    /// Gets the raw pointer to the underlying value.
+    /// Returns `None` if the cell is empty.
+    ///
+    /// The pointer remains valid for reads as long as the cell is neither dropped, moved, nor emptied
+    /// (e.g. via [OptionCell::take] or [OptionCell::get_mut]).
+    pub fn get_raw(&self) -> Option<*const T> {
+        self.get().map(|value| value as *const T)
+    }
+
+    /// Gets the mutable raw pointer to the underlying value, e.g. for handing it off to FFI code.
+    /// Returns `None` if the cell is empty.
+    ///
+    /// Obtaining the pointer is safe, but using it is subject to the following conditions:
+    ///
+    /// - The pointer is valid as long as the cell is neither dropped, moved, nor emptied,
+    ///   as with [OptionCell::get_raw].
+    /// - Writing through the pointer requires that no references to the value are alive,
+    ///   including those obtained via [OptionCell::get] and alike, as they assume the value is never mutated.
+    ///   Such references must not be obtained while the write is in progress either.
+    ///
+    /// The pointer is derived from the storage of the cell, not from a shared reference,
+    /// so it carries the write permission of the [UnsafeCell].
+    pub fn value_ptr(&self) -> Option<*mut T> {
+        let value = self.get()? as *const T;
+        let base = self.inner.get();
+        let offset = value as usize - base as usize;
+        // Safety: `value` points to the payload located `offset` bytes into the storage `base` points to.
+        Some(unsafe { base.cast::<u8>().add(offset).cast::<T>() })
+    }
+
+    /// Gets the non-null pointer to the underlying value.
+    /// Returns `None` if the cell is empty.
+    ///
+    /// The same validity conditions as [OptionCell::get_raw] apply: the pointer is only valid for reads,
+    /// and only as long as the cell is neither dropped, moved, nor emptied.
+    pub fn get_non_null(&self) -> Option<NonNull<T>> {
+        self.get().map(NonNull::from)
+    }
+
+    /// Maps the reference to the underlying value with `f`.
+    /// Returns `None` if the cell is empty.
+    ///
+    /// This is equivalent to `self.get().map(f)`.
+    pub fn map_ref<U, F>(&self, f: F) -> Option<U>
+    where
+        F: FnOnce(&T) -> U,
+    {
+        self.get().map(f)
+    }
+
+    /// Maps the reference to the underlying value with `f`, or returns `default` if the cell is empty.
+    pub fn map_or<U, F>(&self, default: U, f: F) -> U
+    where
+        F: FnOnce(&T) -> U,
+    {
+        self.get().map_or(default, f)
+    }
+
+    /// Maps the reference to the underlying value with `f`, or computes the default with `default` if the cell is empty.
+    pub fn map_or_else<U, D, F>(&self, default: D, f: F) -> U
+    where
+        D: FnOnce() -> U,
+        F: FnOnce(&T) -> U,
+    {
+        self.get().map_or_else(default, f)
+    }
+
+    /// Gets the reference to a part of the underlying value, projected by `f`.
+    /// Returns `None` if the cell is empty.
+    pub fn project<U, F>(&self, f: F) -> Option<&U>
+    where
+        U: ?Sized,
+        F: FnOnce(&T) -> &U,
+    {
+        self.get().map(f)
+    }
+
+    /// Returns `true` if the cell is populated.
+    ///
+    /// Unlike other accessors, this method can be called in `const` contexts.
+    pub const fn is_set_const(&self) -> bool {
+        // Safety: critical section can always read, and no references are exposed to the caller.
+        //
+        // It does not use the critical section helper because closures cannot be called in const fn.
+        // Nevertheless it constitutes a critical section.
+        unsafe { &*self.inner.get() }.is_some()
+    }
+
+    /// Gets the reference to the underlying value, without checking that the cell is populated.
+    ///
+    /// # Safety
+    ///
+    /// The cell must be populated.
+    pub unsafe fn value_unchecked(&self) -> &T {
+        match self.get() {
+            Some(value) => value,
+            None => std::hint::unreachable_unchecked(),
+        }
+    }
+
+    /// Gets the reference to the underlying value.
+    /// Returns [EmptyCell] if the cell is empty, so that it can be propagated with `?`.
+    pub fn get_or_err(&self) -> Result<&T, EmptyCell> {
+        self.get().ok_or(EmptyCell)
+    }
+
+    /// Gets a guard to the underlying value.
+    /// Returns `None` if the cell is empty.
+    ///
+    /// The guard is only available when the cell is populated, i.e. when it is in read mode.
+    /// While the guard lives, the cell cannot be mutably borrowed,
+    /// and any attempt to [set](OptionCell::set) it fails as it is already populated.
+    ///
+    /// ```compile_fail
+    /// use option_cell::OptionCell;
+    ///
+    /// let mut cell = OptionCell::from(Some(42));
+    /// let guard = cell.borrow_guard().unwrap();
+    /// cell.take();
+    /// assert_eq!(*guard, 42);
+    /// ```
+    pub fn borrow_guard(&self) -> Option<Guard<'_, T>> {
+        self.get().map(|value| Guard { value })
+    }
+
+    /// Gets a registered shared borrow of the value in the cell.
+    /// Returns `None` if the cell is empty.
+    ///
+    /// Unlike [OptionCell::borrow_guard], the borrow is recorded in a thread-local registry
+    /// until the guard is dropped, and [OptionCell::take_shared] panics while it is alive.
+    /// As the registry is keyed by cell address, the layout of the cell is unaffected.
+    pub fn read(&self) -> Option<ReadGuard<'_, T>> {
+        let value = self.get()?;
+        let addr = self as *const OptionCell<T> as *const ();
+        READING.with(|reading| reading.borrow_mut().push(addr));
+        Some(ReadGuard { value, addr })
+    }
+
+    /// Gets a read-only view of the cell.
+    pub fn reader(&self) -> CellReader<'_, T> {
+        CellReader { cell: self }
+    }
+
+    /// Returns a copy of the contents without consuming the cell.
+    /// Returns `None` if the cell is empty.
+    pub fn get_copy(&self) -> Option<T>
+    where
+        T: Copy,
+    {
+        self.get().copied()
+    }
+
+    /// Returns a clone of the contents as an Option, without consuming the cell.
+    ///
+    /// This is the cloning counterpart of [OptionCell::into_option].
+    pub fn to_option(&self) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.get().cloned()
+    }
+
+    /// Returns a clone of the contents, or `default` if the cell is empty.
+    pub fn get_cloned_or(&self, default: T) -> T
+    where
+        T: Clone,
+    {
+        self.get().cloned().unwrap_or(default)
+    }
+
+    /// Returns an independent cell holding a clone of the current contents.
+    ///
+    /// This is the same as [Clone::clone]. Later changes to either cell do not affect the other.
+    pub fn snapshot(&self) -> OptionCell<T>
+    where
+        T: Clone,
+    {
+        self.clone()
+    }
+
+    /// Replaces the contents of `target` with a clone of the contents of this cell.
+    ///
+    /// If both cells are populated, the resources of `target` are reused via [Clone::clone_from].
+    pub fn clone_into(&self, target: &mut OptionCell<T>)
+    where
+        T: Clone,
+    {
+        match (self.get(), target.get_mut()) {
+            (Some(value), Some(target)) => target.clone_from(value),
+            (Some(value), target) => *target = Some(value.clone()),
+            (None, target) => *target = None,
+        }
+    }
+
+    /// Fills `buf` with a clone of the contents, if the cell is populated.
+    /// `buf` is left untouched if the cell is empty.
+    ///
+    /// If `buf` is already populated, its resources are reused via [Clone::clone_from].
+    pub fn get_or_clone_into(&self, buf: &mut Option<T>)
+    where
+        T: Clone,
+    {
+        if let Some(value) = self.get() {
+            match buf {
+                Some(buf) => buf.clone_from(value),
+                None => *buf = Some(value.clone()),
+            }
+        }
+    }
+
+    /// Returns `true` if the cell is populated with a value equal to `value`.
+    /// An empty cell is never equal to a value.
+    ///
+    /// This is the counterpart of comparing with a bare `T`: `PartialEq<T>` cannot be implemented,
+    /// as it would overlap with `PartialEq<OptionCell<U>>` for `OptionCell<OptionCell<U>>`.
+    pub fn contains<U>(&self, value: &U) -> bool
+    where
+        T: PartialEq<U>,
+    {
+        self.get().is_some_and(|x| x == value)
+    }
+
+    /// Returns `true` only if both cells are populated with equal values.
+    ///
+    /// Unlike `==`, two empty cells are not considered equal, as there are no values to compare.
+    pub fn value_eq(&self, other: &Self) -> bool
+    where
+        T: PartialEq,
+    {
+        match (self.get(), other.get()) {
+            (Some(x), Some(y)) => x == y,
+            _ => false,
+        }
+    }
+
+    /// Compares the contents of two cells.
+    ///
+    /// As in Option, an empty cell is less than any populated cell.
+    pub fn cmp_by_value(&self, other: &Self) -> Ordering
+    where
+        T: Ord,
+    {
+        self.get().cmp(&other.get())
+    }
+
+    /// Returns a [Debug](fmt::Debug) representation that only tells whether the cell is populated.
+    ///
+    /// It prints `OptionCell(set)` or `OptionCell(empty)` without formatting the contents, so `T` need not be `Debug`.
+    pub fn debug_compact(&self) -> impl fmt::Debug + '_ {
+        DebugCompact { cell: self }
+    }
+
+    /// Gets the shared reference to the underlying Option.
+    ///
+    /// This is the read-only analog of [OptionCell::get_mut].
+    /// If the cell is populated, the reference points to the storage of the cell.
+    /// If it is empty, the reference points to a separate `None` instead:
+    /// the storage must not be borrowed as a whole while it is in write mode,
+    /// since a later [set](OptionCell::set) would change it behind the shared reference.
+    /// The returned reference therefore keeps reading `None` even after the cell is populated.
+    pub fn as_option_ref(&self) -> &Option<T> {
+        // Safety: critical section can always read. Then,
+        // - If it is Some(_), it is in read mode, which lasts as long as the shared borrow of the cell.
+        // - If it is None, the reference is dropped before returning.
+        let opt = unsafe { &*self.inner.get() };
+        if opt.is_some() {
+            opt
+        } else {
+            &None
+        }
+    }
+
     /// Gets the mutable reference to the underlying Option.
     ///
     /// Unlike the original OnceCell, this method returns a mutable reference to the whole Option<T>,
@@ -93,20 +438,121 @@ impl<T> OptionCell<T> {
         unsafe { &mut *self.inner.get() }
     }
 
+    /// Gets the mutable reference to the underlying Option, along with whether the cell was populated.
+    pub fn get_mut_status(&mut self) -> (&mut Option<T>, bool) {
+        let opt = self.get_mut();
+        let was_set = opt.is_some();
+        (opt, was_set)
+    }
+
     /// Sets the contents of this cell to `value`.
     pub fn set(&self, value: T) -> Result<(), T> {
+        self.set_and_get(value).map(|_| ())
+    }
+
+    /// Sets the contents of this cell to `new` if the current contents match `current`.
+    /// Otherwise, `new` is given back.
+    ///
+    /// As a populated cell is never overwritten through a shared reference,
+    /// the operation can only succeed when both `current` and the cell are empty.
+    /// A `Some(_)` expectation always fails, even if it matches the contents,
+    /// so this is the same as [OptionCell::set] when `current` is `None`, and `Err(new)` otherwise.
+    /// The expected value itself is never compared.
+    pub fn compare_and_set(&self, current: Option<&T>, new: T) -> Result<(), T> {
+        match current {
+            None => self.set(new),
+            Some(_) => Err(new),
+        }
+    }
+
+    /// Sets the contents of this cell to `value` converted into `T`.
+    ///
+    /// The conversion happens only if the cell is empty,
+    /// so the original `value` is given back unconverted if the cell was populated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the conversion populates the cell, as in [OptionCell::get_or_init].
+    pub fn set_into<V>(&self, value: V) -> Result<(), V>
+    where
+        V: Into<T>,
+    {
+        if self.is_set_const() {
+            Err(value)
+        } else {
+            self.set(value.into())
+                .unwrap_or_else(|_| panic!("Recursive initialization within set_into"));
+            Ok(())
+        }
+    }
+
+    /// Sets the contents of this cell to `value`.
+    /// Returns `true` on success, or drops `value` and returns `false` if the cell was populated.
+    pub fn try_set(&self, value: T) -> bool {
+        self.set(value).is_ok()
+    }
+
+    /// Sets the contents of this cell to `value`.
+    ///
+    /// Unlike [OptionCell::set], the rejected value is dropped and [OptionCellError::AlreadySet] is returned
+    /// so that the error can be propagated with `?`.
+    pub fn set_or_err(&self, value: T) -> Result<(), OptionCellError> {
+        self.set(value).map_err(|_| OptionCellError::AlreadySet)
+    }
+
+    /// Sets the contents of this cell to `value`.
+    ///
+    /// Returns the reference to the stored value on success, or gives back `value` if the cell was populated.
+    pub fn set_returning(&self, value: T) -> SetOutcome<'_, T> {
+        match self.set_and_get(value) {
+            Ok(value) => SetOutcome::Inserted(value),
+            Err(value) => SetOutcome::Rejected(value),
+        }
+    }
+
+    /// Sets the contents of this cell to `value` and returns the reference to the stored value.
+    fn set_and_get(&self, value: T) -> Result<&T, T> {
         let is_none = unsafe { self.critical_read_section(|opt| opt.is_none()) };
         if is_none {
-            unsafe {
-                self.critical_write_section(|opt| *opt = Some(value));
-            }
-            Ok(())
+            let ptr = unsafe { self.critical_write_section(|opt| opt.insert(value) as *const T) };
+            #[cfg(feature = "trace")]
+            trace::notify(self as *const Self as *const ());
+            // Safety: the cell is now in read mode, which lasts as long as the shared borrow of the cell.
+            Ok(unsafe { &*ptr })
         } else {
             Err(value)
         }
     }
 
     /// Gets the contents of the cell, initializing with `f` if the cell was empty.
+    ///
+    /// `f` is only called when the cell is empty, which makes the cell usable as a memoization cache:
+    ///
+    /// ```rust
+    /// use option_cell::OptionCell;
+    ///
+    /// struct Term {
+    ///     name: String,
+    ///     rendered: OptionCell<String>,
+    /// }
+    ///
+    /// impl Term {
+    ///     fn render(&self) -> &str {
+    ///         self.rendered.get_or_init(|| format!("<{}>", self.name))
+    ///     }
+    /// }
+    ///
+    /// let term = Term { name: "x".to_owned(), rendered: OptionCell::new() };
+    /// assert_eq!(term.render(), "<x>");
+    /// ```
+    ///
+    /// If `f` panics, the panic is propagated and the cell is left empty, so the initialization can be retried.
+    /// No partial write happens, as the cell is only touched after `f` returns.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `f` populates the cell, e.g. by calling this method reentrantly.
+    /// The value returned by `f` is dropped before panicking, while the cell holds the value set from within `f`.
     pub fn get_or_init<F>(&self, f: F) -> &T
     where
         F: FnOnce() -> T,
@@ -114,153 +560,2779 @@ impl<T> OptionCell<T> {
         if let Some(value) = self.get() {
             value
         } else {
+            // `f` runs outside of any critical section, so the cell stays in write mode if it panics.
             let value = f();
-            if self.set(value).is_err() {
-                panic!("Recursive initialization within get_or_init");
+            match self.set_and_get(value) {
+                Ok(value) => value,
+                Err(rejected) => {
+                    drop(rejected);
+                    panic!("Recursive initialization within get_or_init")
+                }
             }
-            self.get().unwrap()
         }
     }
 
-    /// Consumes the cell, returning the wrapped Option<T>.
-    pub fn into_inner(self) -> Option<T> {
-        self.inner.into_inner()
+    /// Gets the contents of the cell, initializing with `T::default()` if the cell was empty.
+    pub fn get_or_default(&self) -> &T
+    where
+        T: Default,
+    {
+        self.get_or_init(T::default)
     }
 
-    /// Takes the value out of this cell, leaving it empty.
-    pub fn take(&mut self) -> Option<T> {
-        self.get_mut().take()
+    /// Gets the contents of the cell, initializing with the asynchronous `f` if the cell was empty.
+    ///
+    /// The cell is single-threaded, but tasks on the same thread may still interleave while `f` is pending.
+    /// Concurrent tasks must not race to initialize the same cell:
+    /// if the cell is populated while `f` is pending, this method panics as in [OptionCell::get_or_init].
+    #[cfg(feature = "async")]
+    pub async fn get_or_init_async<F, Fut>(&self, f: F) -> &T
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = T>,
+    {
+        if let Some(value) = self.get() {
+            return value;
+        }
+        let value = f().await;
+        match self.set_and_get(value) {
+            Ok(value) => value,
+            Err(_) => panic!("Recursive initialization within get_or_init_async"),
+        }
     }
 
-    /// Converts an existing mutable reference into OptionCell.
-    pub fn from_mut(slice: &mut Option<T>) -> &mut Self {
-        // Safety: layout is compatible as observed in Cell.
-        // The ownership invariant is the same.
-        unsafe { &mut *(slice as *mut Option<T> as *mut Self) }
+    /// Gets the contents of the cell, initializing with the boxed `f` if the cell was empty.
+    ///
+    /// This is the same as [OptionCell::get_or_init], which also accepts boxed closures directly.
+    pub fn get_or_init_dyn(&self, f: Box<dyn FnOnce() -> T + '_>) -> &T {
+        self.get_or_init(f)
     }
 
-    /// Converts an existing mutable slice into a slice of OptionCell.
-    pub fn from_mut_slice(slice: &mut [Option<T>]) -> &mut [Self] {
-        // Safety: layout is compatible as observed in Cell.
-        // The ownership invariant is the same.
-        unsafe { std::slice::from_raw_parts_mut(slice.as_mut_ptr() as *mut Self, slice.len()) }
+    /// Gets the contents of the cell, initializing with `f` if the cell was empty.
+    /// If `f` reentrantly initializes the same cell, the inner call initializes it with `on_cycle` instead.
+    ///
+    /// Reentrancy is tracked per thread by the address of the cell.
+    /// The first value stored wins: once the cycle fallback populates the cell,
+    /// the value later returned by the outer `f` is dropped and the fallback value is returned from both calls.
+    ///
+    /// The rejected value is dropped after the reference to the existing value is obtained,
+    /// so its destructor may freely inspect the cell.
+    pub fn get_or_init_or_cycle<F, G>(&self, f: F, on_cycle: G) -> &T
+    where
+        F: FnOnce() -> T,
+        G: FnOnce() -> T,
+    {
+        if let Some(value) = self.get() {
+            return value;
+        }
+        let value = match InitTracker::enter(self) {
+            Some(_tracker) => f(),
+            None => on_cycle(),
+        };
+        match self.set_and_get(value) {
+            Ok(value) => value,
+            Err(rejected) => {
+                // The cell has been populated in the meantime, and the existing value wins.
+                let existing = self.get().unwrap();
+                drop(rejected);
+                existing
+            }
+        }
     }
-}
 
-impl<T> From<Option<T>> for OptionCell<T> {
-    fn from(opt: Option<T>) -> Self {
-        Self {
-            inner: UnsafeCell::new(opt),
+    /// Gets the contents of the cell, initializing with the fallible `f` if the cell was empty.
+    ///
+    /// Unlike [OptionCell::get_or_init], this method does not panic on reentrant initialization.
+    /// Instead, [InitError::Recursion] is returned from the reentrant call,
+    /// or from the outer call if `f` populated the cell by other means.
+    /// In the latter case, the value returned by `f` is dropped before returning,
+    /// while the cell holds the value set from within `f`.
+    /// If `f` fails, the cell remains empty and the error is returned as [InitError::Closure].
+    pub fn get_or_try_init_checked<F, E>(&self, f: F) -> Result<&T, InitError<E>>
+    where
+        F: FnOnce() -> Result<T, E>,
+    {
+        if let Some(value) = self.get() {
+            return Ok(value);
         }
+        let value = {
+            let _tracker = InitTracker::enter(self).ok_or(InitError::Recursion)?;
+            f().map_err(InitError::Closure)?
+        };
+        self.set_and_get(value).map_err(|rejected| {
+            drop(rejected);
+            InitError::Recursion
+        })
     }
-}
 
-impl<T> Default for OptionCell<T> {
-    fn default() -> Self {
-        OptionCell::from(None)
+    /// Gets the contents of the cell, initializing with the fallible `f` if the cell was empty,
+    /// retrying up to `retries` times on failure.
+    ///
+    /// `f` is called at most `retries + 1` times, and the first success populates the cell.
+    /// If every attempt fails, the cell remains empty and the last error is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `f` populates the cell, as in [OptionCell::get_or_init].
+    pub fn get_or_try_init_retry<F, E>(&self, retries: usize, mut f: F) -> Result<&T, E>
+    where
+        F: FnMut() -> Result<T, E>,
+    {
+        if let Some(value) = self.get() {
+            return Ok(value);
+        }
+        let mut failures = 0;
+        let value = loop {
+            match f() {
+                Ok(value) => break value,
+                Err(e) if failures == retries => return Err(e),
+                Err(_) => failures += 1,
+            }
+        };
+        match self.set_and_get(value) {
+            Ok(value) => Ok(value),
+            Err(rejected) => {
+                drop(rejected);
+                panic!("Recursive initialization within get_or_try_init_retry")
+            }
+        }
     }
-}
 
-impl<T> From<OptionCell<T>> for Option<T> {
-    fn from(cell: OptionCell<T>) -> Self {
-        cell.into_inner()
+    /// Gets the pointer to the contents of the cell, initializing with the C function `init` if the cell was empty.
+    ///
+    /// This is a variant of [OptionCell::get_or_init] suitable for exposing through an FFI boundary.
+    ///
+    /// Obtaining the pointer is safe, but dereferencing it is subject to the following conditions:
+    ///
+    /// - The pointer is valid as long as the cell is neither dropped nor moved.
+    /// - The pointer must be invalidated once the cell is mutably borrowed (e.g. via [OptionCell::get_mut] or [OptionCell::take]).
+    /// - The pointee must not be mutated through the pointer.
+    pub fn get_or_init_raw(&self, init: extern "C" fn() -> T) -> *const T {
+        self.get_or_init(|| init()) as *const T
     }
-}
 
-impl<T> Clone for OptionCell<T>
-where
-    T: Clone,
-{
-    fn clone(&self) -> Self {
-        OptionCell::from(self.get().cloned())
+    /// Gets the mutable reference to the contents of the cell, initializing with `f` if the cell was empty.
+    ///
+    /// If the cell is populated, `f` is not called and the existing value is left untouched.
+    ///
+    /// The value returned by `f` is written directly into the cell's storage.
+    /// Note that Rust has no guaranteed placement semantics, so eliding the intermediate move is up to the optimizer.
+    pub fn emplace<F>(&mut self, f: F) -> &mut T
+    where
+        F: FnOnce() -> T,
+    {
+        self.get_mut().get_or_insert_with(f)
     }
-}
 
-impl<T> PartialEq<OptionCell<T>> for OptionCell<T>
-where
-    T: PartialEq<T>,
-{
-    fn eq(&self, other: &Self) -> bool {
-        self.get() == other.get()
+    /// Gets the mutable reference to the contents of the cell, initializing with `f` if the cell was empty.
+    ///
+    /// This is the exclusive counterpart of [OptionCell::get_or_init], and is the same as [OptionCell::emplace].
+    pub fn get_or_init_mut<F>(&mut self, f: F) -> &mut T
+    where
+        F: FnOnce() -> T,
+    {
+        self.emplace(f)
     }
 
-    fn ne(&self, other: &Self) -> bool {
-        self.get() != other.get()
+    /// Gets the mutable reference to the contents of the cell, initializing with `T::default()` if the cell was empty.
+    pub fn get_mut_or_default(&mut self) -> &mut T
+    where
+        T: Default,
+    {
+        self.emplace(T::default)
     }
-}
 
-impl<T> fmt::Debug for OptionCell<T>
+    /// Consumes the cell, returning the wrapped Option<T>.
+    pub fn into_inner(self) -> Option<T> {
+        self.inner.into_inner()
+    }
+
+    /// Consumes the cell, returning the wrapped `Option<T>`.
+    ///
+    /// This is the same as [OptionCell::into_inner], named after [OptionCell::to_option].
+    pub fn into_option(self) -> Option<T> {
+        self.into_inner()
+    }
+
+    /// Consumes the cell, returning the value if it is populated, or the empty cell back otherwise.
+    ///
+    /// This is what `T::try_from(cell)` would do,
+    /// but `impl<T> TryFrom<OptionCell<T>> for T` is ruled out by the orphan rule.
+    pub fn try_into_value(self) -> Result<T, Self> {
+        match self.into_inner() {
+            Some(value) => Ok(value),
+            None => Err(OptionCell::new()),
+        }
+    }
+
+    /// Takes the value out of this cell, leaving it empty.
+    pub fn take(&mut self) -> Option<T> {
+        self.get_mut().take()
+    }
+
+    /// Deliberately overrides the contents with `value`, returning the old contents.
+    ///
+    /// Unlike [OptionCell::set], this succeeds even if the cell is populated, which is sound as it requires exclusive access.
+    pub fn force_set(&mut self, value: T) -> Option<T> {
+        self.get_mut().replace(value)
+    }
+
+    /// Replaces the contents with the result of `f`, returning the old contents.
+    ///
+    /// `f` receives the old contents by reference, so they can be both inspected and returned.
+    pub fn replace_with<F>(&mut self, f: F) -> Option<T>
+    where
+        F: FnOnce(Option<&T>) -> Option<T>,
+    {
+        let new = f(self.get());
+        std::mem::replace(self.get_mut(), new)
+    }
+
+    /// Takes the value out of this cell through a shared reference, leaving it empty.
+    ///
+    /// This transitions the cell from read mode back to write mode.
+    ///
+    /// # Safety
+    ///
+    /// No references to the contents of the cell may be alive,
+    /// including those obtained via [OptionCell::get], [OptionCell::borrow_guard], [OptionCell::reader]
+    /// or [OptionCell::assert_all_set], and the raw pointers obtained via [OptionCell::get_raw] and alike.
+    /// The cell must not be pinned via [OptionCell::from_pinned_mut_slice] either, as the value is moved out.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a [ReadGuard] obtained via [OptionCell::read] for this cell is alive.
+    /// Only those guards are checked; the other borrows listed above are not.
+    pub unsafe fn take_shared(&self) -> Option<T> {
+        let addr = self as *const OptionCell<T> as *const ();
+        if READING.with(|reading| reading.borrow().contains(&addr)) {
+            panic!("take_shared while a ReadGuard is alive");
+        }
+        // Safety: critical section can always read.
+        // If it is Some(_), the caller guarantees that no one else holds the read access,
+        // so the control has the write access to the whole Option<T>.
+        // If it is None, it is in write mode.
+        unsafe { (*self.inner.get()).take() }
+    }
+
+    /// Takes the value out of this cell only if it is equal to `expected`.
+    /// Otherwise, the cell is left untouched and `None` is returned.
+    pub fn take_if_eq<U>(&mut self, expected: &U) -> Option<T>
+    where
+        U: PartialEq<T> + ?Sized,
+    {
+        match self.get() {
+            Some(value) if *expected == *value => self.take(),
+            _ => None,
+        }
+    }
+
+    /// Takes the value out of this cell, leaving it empty.
+    /// Returns `default` if the cell was empty.
+    pub fn take_or(&mut self, default: T) -> T {
+        self.take().unwrap_or(default)
+    }
+
+    /// Takes the value out of this cell, leaving it empty.
+    /// Returns `T::default()` if the cell was empty.
+    pub fn take_or_default(&mut self) -> T
+    where
+        T: Default,
+    {
+        self.take().unwrap_or_default()
+    }
+
+    /// Converts an existing mutable reference into OptionCell.
+    ///
+    /// Options behind smart pointers or guards can be converted by reborrowing, as in `OptionCell::from_mut(&mut *guard)`.
+    /// The resulting cell borrows the pointer mutably for its whole lifetime.
+    pub fn from_mut(slice: &mut Option<T>) -> &mut Self {
+        // Safety: layout is compatible as observed in Cell.
+        // The ownership invariant is the same.
+        unsafe { &mut *(slice as *mut Option<T> as *mut Self) }
+    }
+
+    /// Converts an existing mutable slice into a slice of OptionCell.
+    ///
+    /// This plays the role of [Cell::as_slice_of_cells](std::cell::Cell::as_slice_of_cells).
+    /// Unsized payloads such as `OptionCell<[T]>` are not supported, as `Option<[T]>` itself cannot exist;
+    /// the slice of options is instead converted directly into a slice of cells.
+    pub fn from_mut_slice(slice: &mut [Option<T>]) -> &mut [Self] {
+        // Safety: layout is compatible as observed in Cell.
+        // The ownership invariant is the same.
+        unsafe { std::slice::from_raw_parts_mut(slice.as_mut_ptr() as *mut Self, slice.len()) }
+    }
+
+    /// Converts an array of Option into an array of OptionCell.
+    ///
+    /// This is provided as a function as `From` cannot be implemented between arrays due to the orphan rule.
+    pub fn from_array<const N: usize>(array: [Option<T>; N]) -> [Self; N] {
+        array.map(Self::from)
+    }
+
+    /// Converts an existing pinned mutable slice into a pinned slice of OptionCell.
+    pub fn from_pinned_mut_slice(slice: Pin<&mut [Option<T>]>) -> Pin<&mut [Self]> {
+        // Safety: the conversion only reinterprets the slice in place, so nothing is moved.
+        // The contents of OptionCell can only be moved out through `&mut Self`,
+        // which cannot be obtained from the pinned slice unless `T: Unpin`.
+        unsafe { slice.map_unchecked_mut(|slice| Self::from_mut_slice(slice)) }
+    }
+
+    /// Reinterprets the well-aligned middle part of a byte buffer as a slice of OptionCell.
+    ///
+    /// The unaligned prefix and the trailing bytes not filling a whole cell are ignored.
+    ///
+    /// # Safety
+    ///
+    /// Each `size_of::<Option<T>>()` bytes of the middle part must be a valid bit pattern of `Option<T>`.
+    /// Note that only a few types, such as `Option<NonZeroU32>`, have a defined layout for `Option<T>`.
+    ///
+    /// The values in the buffer are not dropped when the buffer is dropped,
+    /// so `T` should usually be a plain data type.
+    pub unsafe fn from_bytes_mut(bytes: &mut [u8]) -> &mut [Self] {
+        // Safety: alignment is ensured by align_to_mut, and validity by the caller.
+        let (_, cells, _) = unsafe { bytes.align_to_mut::<Self>() };
+        cells
+    }
+
+    /// Converts a vector of OptionCell into a vector of Option, reusing the allocation.
+    ///
+    /// The capacity of the vector is preserved. See [OptionCell::into_vec_exact] for the shrinking variant.
+    pub fn into_vec(vec: Vec<Self>) -> Vec<Option<T>> {
+        let mut vec = std::mem::ManuallyDrop::new(vec);
+        let (ptr, len, cap) = (vec.as_mut_ptr(), vec.len(), vec.capacity());
+        // Safety: layout is compatible as observed in Cell.
+        // The ownership invariant is the same.
+        // The original vector is not dropped, so the allocation is transferred.
+        unsafe { Vec::from_raw_parts(ptr as *mut Option<T>, len, cap) }
+    }
+
+    /// Converts a vector of OptionCell into a vector of Option, shrinking the capacity to fit.
+    pub fn into_vec_exact(vec: Vec<Self>) -> Vec<Option<T>> {
+        let mut vec = Self::into_vec(vec);
+        vec.shrink_to_fit();
+        vec
+    }
+
+    /// Gets the contents of `cells[i]`, initializing with `f` if the cell was empty.
+    ///
+    /// `f` receives the whole slice, so it can read the neighbors of the cell.
+    /// This is sound because `f` only gets shared access to the cells, as in [OptionCell::get_or_init];
+    /// if `f` populates `cells[i]` itself, it is detected as a recursive initialization.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of bounds, or if `f` populates `cells[i]`.
+    pub fn get_or_init_with_neighbors<F>(cells: &[Self], i: usize, f: F) -> &T
+    where
+        F: FnOnce(&[Self]) -> T,
+    {
+        cells[i].get_or_init(|| f(cells))
+    }
+
+    /// Initializes the empty cells in the given order.
+    ///
+    /// For each index in `order`, the cell is initialized with `f` as in [OptionCell::get_or_init].
+    /// `f` receives the index and the whole slice, so it can read the cells initialized earlier.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an index in `order` is out of bounds, or if `f` initializes the cell it is asked for.
+    pub fn init_in_order<F>(cells: &[Self], order: &[usize], mut f: F)
+    where
+        F: FnMut(usize, &[Self]) -> T,
+    {
+        for &i in order {
+            cells[i].get_or_init(|| f(i, cells));
+        }
+    }
+
+    /// Visits each empty cell and populates it with the value returned by `f`, if any.
+    /// Returns the number of cells actually populated.
+    pub fn fill_counting<F>(cells: &[Self], f: F) -> usize
+    where
+        F: FnMut(usize) -> Option<T>,
+    {
+        let mut count = 0;
+        Self::fill_with(cells, f, |_| count += 1);
+        count
+    }
+
+    /// Visits each empty cell and populates it with the value returned by `f`, if any,
+    /// calling `on_fill` with the index of each cell actually populated.
+    fn fill_with<F, G>(cells: &[Self], mut f: F, mut on_fill: G)
+    where
+        F: FnMut(usize) -> Option<T>,
+        G: FnMut(usize),
+    {
+        for (i, cell) in cells.iter().enumerate() {
+            if cell.is_set_const() {
+                continue;
+            }
+            if let Some(value) = f(i) {
+                if cell.set(value).is_ok() {
+                    on_fill(i);
+                }
+            }
+        }
+    }
+
+    /// Gets the cell at `index`, or `None` if the index is out of bounds.
+    ///
+    /// This is the same as `cells.get(index)`; the returned cell can be populated through the shared reference.
+    pub fn get_cell(cells: &[Self], index: usize) -> Option<&Self> {
+        cells.get(index)
+    }
+
+    /// Sets the contents of `cells[index]` to `value`.
+    ///
+    /// The value is given back if the index is out of bounds or the cell is already populated.
+    pub fn set_at(cells: &[Self], index: usize, value: T) -> Result<(), SetError<T>> {
+        match cells.get(index) {
+            Some(cell) => cell.set(value).map_err(SetError::AlreadySet),
+            None => Err(SetError::OutOfBounds(value)),
+        }
+    }
+
+    /// Visits each empty cell and populates it with the value returned by `f`, if any,
+    /// appending the index of each populated cell to `log` in the order they are populated.
+    ///
+    /// The cells that are already populated, or for which `f` returns `None`, are not logged.
+    pub fn init_logged<F>(cells: &[Self], f: F, log: &mut Vec<usize>)
+    where
+        F: FnMut(usize) -> Option<T>,
+    {
+        Self::fill_with(cells, f, |i| log.push(i));
+    }
+
+    /// Populates every empty cell with a clone of `value`, leaving the populated cells untouched.
+    pub fn set_all_empty(cells: &[Self], value: T)
+    where
+        T: Clone,
+    {
+        for cell in cells {
+            if !cell.is_set_const() {
+                let _ = cell.set(value.clone());
+            }
+        }
+    }
+
+    /// Collects the references to the values of the cells, preserving the empty ones as `None`.
+    pub fn view_all(cells: &[Self]) -> Vec<Option<&T>> {
+        cells.iter().map(Self::get).collect()
+    }
+
+    /// Iterates over the values of the populated cells, skipping the empty ones.
+    pub fn flatten_slice(cells: &[Self]) -> impl Iterator<Item = &T> {
+        cells.iter().filter_map(Self::get)
+    }
+
+    /// Checks that every cell is populated, returning a witness that allows unchecked access.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the index of the first empty cell, if any.
+    pub fn assert_all_set(cells: &[Self]) -> Populated<'_, T> {
+        if let Some(i) = cells.iter().position(|cell| !cell.is_set_const()) {
+            panic!("cell at index {} is empty", i);
+        }
+        Populated { cells }
+    }
+
+    /// Returns the indices where the contents of the two slices differ, in ascending order.
+    ///
+    /// Two cells are considered equal if both are empty or both hold equal values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slices have different lengths.
+    pub fn diff_slices(a: &[Self], b: &[Self]) -> Vec<usize>
+    where
+        T: PartialEq,
+    {
+        assert_eq!(a.len(), b.len(), "slices have different lengths");
+        a.iter()
+            .zip(b)
+            .enumerate()
+            .filter(|(_, (x, y))| x.get() != y.get())
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Returns the index of the first populated cell whose value satisfies `pred`.
+    pub fn find_set_by<F>(cells: &[Self], mut pred: F) -> Option<usize>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        cells
+            .iter()
+            .position(|cell| cell.get().is_some_and(&mut pred))
+    }
+
+    /// Resolves a chain of dependencies starting from `cells[start]` iteratively, without recursion.
+    ///
+    /// `next(i, cells)` is called for an empty cell `i` and either returns [ChainStep::Value] to populate it,
+    /// or [ChainStep::Depend] to request that another empty cell be resolved first.
+    /// Once the dependency is populated, `next` is called again for `i`.
+    /// The chain is walked with an explicit stack, so arbitrarily deep chains do not overflow the call stack.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an index is out of bounds, if the dependencies are cyclic,
+    /// if a dependency is already populated, or if `next` populates the cell it is asked for.
+    pub fn resolve_chain<F>(cells: &[Self], start: usize, mut next: F) -> &T
+    where
+        F: FnMut(usize, &[Self]) -> ChainStep<T>,
+    {
+        let mut stack = vec![start];
+        let mut on_stack = vec![false; cells.len()];
+        on_stack[start] = true;
+        while let Some(&i) = stack.last() {
+            if cells[i].is_set_const() {
+                stack.pop();
+                on_stack[i] = false;
+                continue;
+            }
+            match next(i, cells) {
+                ChainStep::Value(value) => {
+                    if cells[i].set(value).is_err() {
+                        panic!("Recursive initialization within resolve_chain");
+                    }
+                }
+                ChainStep::Depend(j) => {
+                    if cells[j].is_set_const() {
+                        panic!("Dependency {} of {} is already populated", j, i);
+                    }
+                    if on_stack[j] {
+                        panic!("Cyclic dependency at {}", j);
+                    }
+                    stack.push(j);
+                    on_stack[j] = true;
+                }
+            }
+        }
+        cells[start].get().unwrap()
+    }
+
+    /// Reborrows a slice of cells, possibly with a shorter lifetime.
+    ///
+    /// The compiler usually does this implicitly; this function makes the intent explicit.
+    pub fn reborrow(cells: &[Self]) -> &[Self] {
+        cells
+    }
+
+    /// Reborrows a mutable slice of cells, possibly with a shorter lifetime.
+    ///
+    /// The compiler usually does this implicitly; this function makes the intent explicit,
+    /// and allows the original slice to be used again after the reborrow ends.
+    pub fn reborrow_mut(cells: &mut [Self]) -> &mut [Self] {
+        cells
+    }
+
+    /// Converts a vector of populated cells into a vector of their values.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the index of the first empty cell, if any.
+    pub fn into_values_unwrap(vec: Vec<Self>) -> Vec<T> {
+        vec.into_iter()
+            .enumerate()
+            .map(|(i, cell)| match cell.into_inner() {
+                Some(value) => value,
+                None => panic!("cell at index {} is empty", i),
+            })
+            .collect()
+    }
+
+    /// Consumes a vector of OptionCell, yielding the underlying options in order.
+    ///
+    /// This is the same as `OptionCell::into_vec(vec).into_iter()`.
+    pub fn into_options(vec: Vec<Self>) -> impl Iterator<Item = Option<T>> {
+        Self::into_vec(vec).into_iter()
+    }
+
+    /// Removes the populated cells whose value satisfies `pred` from the vector, returning their values in order.
+    ///
+    /// The remaining cells, including the empty ones, keep their relative order.
+    pub fn drain_filter_set<F>(vec: &mut Vec<Self>, mut pred: F) -> Vec<T>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut removed = Vec::new();
+        vec.retain_mut(|cell| {
+            if cell.get().is_some_and(&mut pred) {
+                removed.extend(cell.take());
+                false
+            } else {
+                true
+            }
+        });
+        removed
+    }
+
+    /// Collects the values of the cells into a populated cell, only if every cell is populated.
+    /// Otherwise, stops at the first empty cell and returns an empty cell.
+    pub fn collect_all<I>(iter: I) -> OptionCell<Vec<T>>
+    where
+        I: IntoIterator<Item = Self>,
+    {
+        OptionCell::from(
+            iter.into_iter()
+                .map(Self::into_inner)
+                .collect::<Option<Vec<T>>>(),
+        )
+    }
+
+    /// Divides a mutable slice of cells into two at an index.
+    ///
+    /// The first slice contains the cells in `[0, mid)` and the second one contains `[mid, len)`.
+    /// This is the same as [slice::split_at_mut], provided for discoverability.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid > cells.len()`.
+    pub fn split_cells_mut(cells: &mut [Self], mid: usize) -> (&mut [Self], &mut [Self]) {
+        cells.split_at_mut(mid)
+    }
+}
+
+impl OptionCell<()> {
+    /// Marks the cell, using it as a set-once flag.
+    /// Returns `true` if the cell was newly marked, `false` if it was already marked.
+    ///
+    /// This is the same as `self.set(()).is_ok()`; as `Option<()>` is a single byte, it is a plain flag write.
+    pub fn mark(&self) -> bool {
+        self.set(()).is_ok()
+    }
+}
+
+impl<U: ?Sized> OptionCell<Box<U>> {
+    /// Gets the reference to the boxed value.
+    /// Returns `None` if the cell is empty.
+    pub fn get_deref(&self) -> Option<&U> {
+        self.get().map(|value| &**value)
+    }
+}
+
+impl<U: ?Sized> OptionCell<Weak<U>> {
+    /// Upgrades the weak pointer in the cell.
+    /// Returns `None` if the cell is empty or the pointee has been dropped.
+    pub fn upgrade(&self) -> Option<Rc<U>> {
+        self.get().and_then(Weak::upgrade)
+    }
+}
+
+thread_local! {
+    /// Addresses of the cells being initialized in the current thread.
+    static INITIALIZING: RefCell<Vec<*const ()>> = const { RefCell::new(Vec::new()) };
+    /// Addresses of the cells borrowed via [OptionCell::read] in the current thread, once per live guard.
+    static READING: RefCell<Vec<*const ()>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Marks a cell as being initialized in the current thread until dropped.
+struct InitTracker {
+    addr: *const (),
+}
+
+impl InitTracker {
+    /// Returns `None` if the cell is already being initialized in the current thread.
+    fn enter<T>(cell: &OptionCell<T>) -> Option<Self> {
+        let addr = cell as *const OptionCell<T> as *const ();
+        INITIALIZING.with(|initializing| {
+            let mut initializing = initializing.borrow_mut();
+            if initializing.contains(&addr) {
+                None
+            } else {
+                initializing.push(addr);
+                Some(InitTracker { addr })
+            }
+        })
+    }
+}
+
+impl Drop for InitTracker {
+    fn drop(&mut self) {
+        INITIALIZING.with(|initializing| {
+            let mut initializing = initializing.borrow_mut();
+            if let Some(pos) = initializing.iter().rposition(|&addr| addr == self.addr) {
+                initializing.remove(pos);
+            }
+        });
+    }
+}
+
+/// The return type of [OptionCell::debug_compact].
+struct DebugCompact<'a, T> {
+    cell: &'a OptionCell<T>,
+}
+
+impl<T> fmt::Debug for DebugCompact<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.cell.is_set_const() {
+            f.write_str("OptionCell(set)")
+        } else {
+            f.write_str("OptionCell(empty)")
+        }
+    }
+}
+
+/// A slice of cells known to be populated, returned by [OptionCell::assert_all_set].
+pub struct Populated<'a, T> {
+    // Invariant: every cell is populated.
+    // It is maintained as cells cannot be emptied while they are borrowed.
+    cells: &'a [OptionCell<T>],
+}
+
+impl<'a, T> Populated<'a, T> {
+    /// Returns the number of cells.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Returns `true` if there are no cells.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Gets the reference to the value of the cell at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn get(&self, index: usize) -> &'a T {
+        // Safety: every cell is populated by the invariant.
+        unsafe { self.cells[index].value_unchecked() }
+    }
+
+    /// Iterates over the values of the cells.
+    pub fn iter(&self) -> impl Iterator<Item = &'a T> + 'a {
+        // Safety: every cell is populated by the invariant.
+        self.cells
+            .iter()
+            .map(|cell| unsafe { cell.value_unchecked() })
+    }
+}
+
+impl<T> Clone for Populated<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Populated<'_, T> {}
+
+/// A read-only view of an [OptionCell], returned by [OptionCell::reader].
+///
+/// The view does not expose any methods that populate the cell.
+///
+/// ```compile_fail
+/// use option_cell::OptionCell;
+///
+/// let cell = OptionCell::new();
+/// cell.reader().set(42);
+/// ```
+pub struct CellReader<'a, T> {
+    cell: &'a OptionCell<T>,
+}
+
+impl<'a, T> CellReader<'a, T> {
+    /// Gets the reference to the underlying value.
+    /// Returns `None` if the cell is empty.
+    pub fn get(&self) -> Option<&'a T> {
+        self.cell.get()
+    }
+
+    /// Returns `true` if the cell is populated.
+    pub fn is_set(&self) -> bool {
+        self.cell.is_set_const()
+    }
+}
+
+impl<T> Clone for CellReader<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for CellReader<'_, T> {}
+
+impl<T> fmt::Debug for CellReader<'_, T>
 where
     T: fmt::Debug,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_tuple("OptionCell").field(&self.get()).finish()
+        f.debug_tuple("CellReader").field(&self.get()).finish()
+    }
+}
+
+/// The error type of the fallible operations on [OptionCell].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum OptionCellError {
+    /// The cell was already populated.
+    AlreadySet,
+    /// The cell was initialized reentrantly.
+    Recursion,
+    /// The cell was empty.
+    Empty,
+    /// The index was out of bounds.
+    OutOfBounds,
+    /// A previous initializer panicked, as in [locked::PoisonedError].
+    Poisoned,
+}
+
+impl fmt::Display for OptionCellError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OptionCellError::AlreadySet => f.write_str("cell already populated"),
+            OptionCellError::Recursion => f.write_str("recursive initialization"),
+            OptionCellError::Empty => f.write_str("cell empty"),
+            OptionCellError::OutOfBounds => f.write_str("index out of bounds"),
+            OptionCellError::Poisoned => f.write_str("cell poisoned by a panicking initializer"),
+        }
+    }
+}
+
+impl std::error::Error for OptionCellError {}
+
+/// The error returned by [OptionCell::get_or_err].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EmptyCell;
+
+impl fmt::Display for EmptyCell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("cell empty")
+    }
+}
+
+impl std::error::Error for EmptyCell {}
+
+impl From<EmptyCell> for OptionCellError {
+    fn from(_: EmptyCell) -> Self {
+        OptionCellError::Empty
+    }
+}
+
+/// The result of [OptionCell::set_returning].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetOutcome<'a, T> {
+    /// The value was stored in the cell.
+    Inserted(&'a T),
+    /// The cell was already populated, and the value is given back.
+    Rejected(T),
+}
+
+/// The error returned by [OptionCell::set_at], giving the value back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetError<T> {
+    /// The index was out of bounds.
+    OutOfBounds(T),
+    /// The cell was already populated.
+    AlreadySet(T),
+}
+
+impl<T> SetError<T> {
+    /// Returns the value that could not be stored.
+    pub fn into_inner(self) -> T {
+        match self {
+            SetError::OutOfBounds(value) | SetError::AlreadySet(value) => value,
+        }
+    }
+}
+
+impl<T> fmt::Display for SetError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SetError::OutOfBounds(_) => f.write_str("index out of bounds"),
+            SetError::AlreadySet(_) => f.write_str("cell already populated"),
+        }
+    }
+}
+
+impl<T> std::error::Error for SetError<T> where T: fmt::Debug {}
+
+impl<T> From<SetError<T>> for OptionCellError {
+    fn from(e: SetError<T>) -> Self {
+        match e {
+            SetError::OutOfBounds(_) => OptionCellError::OutOfBounds,
+            SetError::AlreadySet(_) => OptionCellError::AlreadySet,
+        }
+    }
+}
+
+/// A step of the chain walked by [OptionCell::resolve_chain].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainStep<T> {
+    /// The cell depends on the cell at the index, which must be resolved first.
+    Depend(usize),
+    /// The value of the cell.
+    Value(T),
+}
+
+/// The error returned by [OptionCell::get_or_try_init_checked].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InitError<E> {
+    /// The initializer returned an error.
+    Closure(E),
+    /// The cell was initialized reentrantly.
+    Recursion,
+}
+
+impl<E> fmt::Display for InitError<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InitError::Closure(e) => write!(f, "initialization failed: {}", e),
+            InitError::Recursion => f.write_str("recursive initialization"),
+        }
+    }
+}
+
+impl<E> std::error::Error for InitError<E>
+where
+    E: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InitError::Closure(e) => Some(e),
+            InitError::Recursion => None,
+        }
+    }
+}
+
+/// Allows nested initializers that fail with [OptionCellError] to converge on a single error type.
+impl<E> From<InitError<E>> for OptionCellError
+where
+    E: Into<OptionCellError>,
+{
+    fn from(e: InitError<E>) -> Self {
+        match e {
+            InitError::Closure(e) => e.into(),
+            InitError::Recursion => OptionCellError::Recursion,
+        }
+    }
+}
+
+/// A shared borrow of the value in a populated [OptionCell], returned by [OptionCell::borrow_guard].
+#[derive(Debug)]
+pub struct Guard<'a, T> {
+    value: &'a T,
+}
+
+impl<T> Deref for Guard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+/// A registered shared borrow of the value in a populated [OptionCell], returned by [OptionCell::read].
+///
+/// The guard is tied to the current thread, as is the registry it is recorded in.
+/// Leaking the guard keeps the borrow registered for the rest of the thread.
+pub struct ReadGuard<'a, T> {
+    value: &'a T,
+    addr: *const (),
+}
+
+impl<T> Deref for ReadGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<T> Drop for ReadGuard<'_, T> {
+    fn drop(&mut self) {
+        READING.with(|reading| {
+            let mut reading = reading.borrow_mut();
+            if let Some(pos) = reading.iter().rposition(|&addr| addr == self.addr) {
+                reading.swap_remove(pos);
+            }
+        });
+    }
+}
+
+impl<T> fmt::Debug for ReadGuard<'_, T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.value, f)
+    }
+}
+
+impl<T> From<Option<T>> for OptionCell<T> {
+    fn from(opt: Option<T>) -> Self {
+        Self {
+            inner: UnsafeCell::new(opt),
+        }
+    }
+}
+
+impl<T> Default for OptionCell<T> {
+    fn default() -> Self {
+        OptionCell::from(None)
+    }
+}
+
+impl<T> From<OptionCell<T>> for Option<T> {
+    fn from(cell: OptionCell<T>) -> Self {
+        cell.into_inner()
+    }
+}
+
+/// Populates the cell with the first element of the iterator, if any.
+/// The remaining elements are not consumed.
+impl<T> FromIterator<T> for OptionCell<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        OptionCell::from(iter.into_iter().next())
+    }
+}
+
+impl<T> Clone for OptionCell<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        OptionCell::from(self.get().cloned())
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        source.clone_into(self)
+    }
+}
+
+impl<T, U> PartialEq<OptionCell<U>> for OptionCell<T>
+where
+    T: PartialEq<U>,
+{
+    fn eq(&self, other: &OptionCell<U>) -> bool {
+        match (self.get(), other.get()) {
+            (Some(x), Some(y)) => x == y,
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<T> Eq for OptionCell<T> where T: Eq {}
+
+impl<T> PartialOrd<OptionCell<T>> for OptionCell<T>
+where
+    T: PartialOrd<T>,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.get().partial_cmp(&other.get())
+    }
+}
+
+impl<T> Ord for OptionCell<T>
+where
+    T: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cmp_by_value(other)
+    }
+}
+
+/// Hashes the same as the underlying `Option<T>`, consistently with [PartialEq].
+///
+/// Beware that populating a cell changes its hash.
+/// Cells used as keys of hash maps must not be populated through a shared reference.
+impl<T> Hash for OptionCell<T>
+where
+    T: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Option<&T> hashes exactly the same as Option<T>: the discriminant, then the value.
+        // Hashing through get() avoids holding a reference to an empty cell while calling into the Hasher,
+        // which could populate the cell reentrantly.
+        self.get().hash(state)
+    }
+}
+
+impl<T> fmt::Debug for OptionCell<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("OptionCell").field(&self.get()).finish()
+    }
+}
+
+/// Formats the address of the underlying storage, identifying the cell rather than its contents.
+impl<T> fmt::Pointer for OptionCell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Pointer::fmt(&self.inner.get(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_get() {
+        let cell = OptionCell::<i32>::new();
+        assert_eq!(cell.get(), None);
+    }
+
+    #[test]
+    fn test_set_get() {
+        let cell = OptionCell::<i32>::new();
+        let cell_ref1 = &cell;
+        let cell_ref2 = &cell;
+        let cell_ref3 = &cell;
+        assert_eq!(cell_ref1.get(), None);
+        cell_ref2.set(42).unwrap();
+        assert_eq!(cell_ref3.get(), Some(&42));
+    }
+
+    #[test]
+    fn test_set_fail_get() {
+        let cell = OptionCell::<i32>::new();
+        cell.set(42).unwrap();
+        let cell_ref1 = &cell;
+        let cell_ref2 = &cell;
+        assert!(cell_ref1.set(43).is_err());
+        assert_eq!(cell_ref2.get(), Some(&42));
+    }
+
+    #[test]
+    fn test_from_mut() {
+        {
+            let mut opt = Some(42);
+            let cell = OptionCell::from_mut(&mut opt);
+            let cell_ref1 = &*cell;
+            let cell_ref2 = &*cell;
+            assert_eq!(cell_ref1.get(), Some(&42));
+            assert!(cell_ref2.set(43).is_err());
+        }
+        {
+            let mut opt = None;
+            let cell = OptionCell::from_mut(&mut opt);
+            assert_eq!(cell.get(), None);
+            assert!(cell.set(43).is_ok());
+            assert_eq!(opt, Some(43));
+        }
+    }
+
+    #[test]
+    fn test_from_mut_slice() {
+        let mut opts = vec![Some(42), None, Some(43)];
+        let cells = OptionCell::from_mut_slice(&mut opts);
+        let cells_ref1 = &*cells;
+        let cells_ref2 = &*cells;
+        let cells_ref3 = &*cells;
+        assert_eq!(cells_ref1.len(), 3);
+        assert_eq!(cells_ref1[0].get(), Some(&42));
+        assert_eq!(cells_ref1[1].get(), None);
+        assert_eq!(cells_ref1[2].get(), Some(&43));
+
+        assert!(cells_ref2[1].set(44).is_ok());
+        assert_eq!(cells_ref3[1].get(), Some(&44));
+    }
+
+    #[test]
+    fn test_split_cells_mut() {
+        let mut opts = vec![Some(42), None, None, Some(43)];
+        let cells = OptionCell::from_mut_slice(&mut opts);
+        let (left, right) = OptionCell::split_cells_mut(cells, 2);
+        assert_eq!(left.len(), 2);
+        assert_eq!(right.len(), 2);
+        assert_eq!(left[0].get(), Some(&42));
+        assert_eq!(right[1].get(), Some(&43));
+
+        left[1].set(44).unwrap();
+        *right[0].get_mut() = Some(45);
+        assert_eq!(opts, vec![Some(42), Some(44), Some(45), Some(43)]);
+    }
+
+    #[test]
+    fn test_get_or_init_raw() {
+        extern "C" fn init() -> i32 {
+            42
+        }
+        extern "C" fn init_other() -> i32 {
+            43
+        }
+        let cell = OptionCell::<i32>::new();
+        let ptr1 = cell.get_or_init_raw(init);
+        let ptr2 = cell.get_or_init_raw(init_other);
+        assert_eq!(ptr1, ptr2);
+        assert_eq!(unsafe { *ptr1 }, 42);
+        assert_eq!(cell.get(), Some(&42));
+    }
+
+    #[test]
+    fn test_from_mut_slice_mut_ref() {
+        use std::mem::size_of;
+        // The niche of &mut T is preserved through the cell.
+        assert_eq!(size_of::<OptionCell<&mut i32>>(), size_of::<&mut i32>());
+
+        let mut a = 1;
+        let mut b = 2;
+        let mut c = 3;
+        {
+            let mut opts = vec![Some(&mut a), None, Some(&mut b)];
+            let cells = OptionCell::from_mut_slice(&mut opts);
+            let cells_ref1 = &*cells;
+            let cells_ref2 = &*cells;
+            assert_eq!(cells_ref1[0].get().map(|x| **x), Some(1));
+            assert!(cells_ref1[1].get().is_none());
+            assert!(cells_ref2[1].set(&mut c).is_ok());
+            assert_eq!(cells_ref2[1].get().map(|x| **x), Some(3));
+
+            // Mutation through the references requires exclusive access.
+            for cell in cells.iter_mut() {
+                if let Some(x) = cell.get_mut() {
+                    **x *= 10;
+                }
+            }
+        }
+        assert_eq!((a, b, c), (10, 20, 30));
+    }
+
+    #[test]
+    fn test_is_set_const() {
+        const { assert!(!OptionCell::<i32>::new().is_set_const()) };
+
+        let cell = OptionCell::<i32>::new();
+        assert!(!cell.is_set_const());
+        cell.set(42).unwrap();
+        assert!(cell.is_set_const());
+    }
+
+    #[test]
+    fn test_borrow_guard() {
+        let cell = OptionCell::<i32>::new();
+        assert!(cell.borrow_guard().is_none());
+
+        cell.set(42).unwrap();
+        let guard = cell.borrow_guard().unwrap();
+        assert_eq!(*guard, 42);
+        assert!(cell.set(43).is_err());
+        assert_eq!(*guard, 42);
+    }
+
+    #[test]
+    fn test_default_non_default_payload() {
+        struct NonDefault(i32);
+
+        #[derive(Default)]
+        struct Container {
+            cell: OptionCell<NonDefault>,
+        }
+
+        let container = Container::default();
+        assert!(container.cell.get().is_none());
+        container.cell.set(NonDefault(42)).ok().unwrap();
+        assert_eq!(container.cell.get().map(|x| x.0), Some(42));
+    }
+
+    #[test]
+    fn test_emplace() {
+        use std::cell::Cell;
+
+        struct Large<'a> {
+            data: [u8; 4096],
+            drops: &'a Cell<usize>,
+        }
+        impl Drop for Large<'_> {
+            fn drop(&mut self) {
+                self.drops.set(self.drops.get() + 1);
+            }
+        }
+
+        let drops = Cell::new(0);
+        let mut cell = OptionCell::new();
+        let value = cell.emplace(|| Large {
+            data: [1; 4096],
+            drops: &drops,
+        });
+        value.data[0] = 2;
+        assert_eq!(drops.get(), 0);
+
+        let value = cell.emplace(|| panic!("must not be called"));
+        assert_eq!(value.data[0], 2);
+        assert_eq!(value.data[1], 1);
+        assert_eq!(drops.get(), 0);
+
+        drop(cell);
+        assert_eq!(drops.get(), 1);
+    }
+
+    #[test]
+    fn test_assert_cell_eq() {
+        let cell = OptionCell::<i32>::new();
+        assert_cell_eq!(cell, None);
+        cell.set(42).unwrap();
+        assert_cell_eq!(cell, Some(42));
+        assert_cell_eq!(&cell, Some(42), "with message {}", 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "cell: OptionCell(Some(42))\nexpected: None")]
+    fn test_assert_cell_eq_fail() {
+        let cell = OptionCell::from(Some(42));
+        assert_cell_eq!(cell, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "failed: custom 1")]
+    fn test_assert_cell_eq_fail_message() {
+        let cell = OptionCell::<i32>::new();
+        assert_cell_eq!(cell, Some(42), "custom {}", 1);
+    }
+
+    #[test]
+    fn test_get_or_init_or_cycle() {
+        let cell = OptionCell::<i32>::new();
+        assert_eq!(*cell.get_or_init_or_cycle(|| 42, || -1), 42);
+        assert_eq!(*cell.get_or_init_or_cycle(|| 43, || -1), 42);
+    }
+
+    #[test]
+    fn test_get_or_init_or_cycle_self_referential() {
+        fn init(cell: &OptionCell<i32>) -> &i32 {
+            cell.get_or_init_or_cycle(|| *init(cell) + 1, || -1)
+        }
+        let cell = OptionCell::<i32>::new();
+        assert_eq!(*init(&cell), -1);
+        assert_eq!(cell.get(), Some(&-1));
+
+        // Other cells are not affected by the tracking.
+        let cell2 = OptionCell::<i32>::new();
+        let value = cell2.get_or_init_or_cycle(
+            || {
+                let cell3 = OptionCell::<i32>::new();
+                *cell3.get_or_init_or_cycle(|| 1, || -1) + 1
+            },
+            || -1,
+        );
+        assert_eq!(*value, 2);
+    }
+
+    #[test]
+    fn test_into_vec() {
+        let mut cells = Vec::with_capacity(10);
+        cells.push(OptionCell::from(Some(42)));
+        cells.push(OptionCell::new());
+        let ptr = cells.as_ptr() as *const Option<i32>;
+
+        let opts = OptionCell::into_vec(cells);
+        assert_eq!(opts, vec![Some(42), None]);
+        assert_eq!(opts.capacity(), 10);
+        assert_eq!(opts.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn test_into_vec_exact() {
+        let mut cells = Vec::with_capacity(10);
+        cells.push(OptionCell::from(Some(42)));
+        cells.push(OptionCell::new());
+
+        let opts = OptionCell::into_vec_exact(cells);
+        assert_eq!(opts, vec![Some(42), None]);
+        assert_eq!(opts.capacity(), 2);
+    }
+
+    #[test]
+    fn test_cmp_by_value() {
+        let empty = OptionCell::<i32>::new();
+        let one = OptionCell::from(Some(1));
+        let two = OptionCell::from(Some(2));
+        assert_eq!(empty.cmp_by_value(&one), Ordering::Less);
+        assert_eq!(two.cmp_by_value(&one), Ordering::Greater);
+        assert_eq!(
+            one.cmp_by_value(&OptionCell::from(Some(1))),
+            Ordering::Equal
+        );
+        assert!(empty < one);
+        assert!(one < two);
+    }
+
+    #[test]
+    fn test_binary_heap() {
+        use std::collections::BinaryHeap;
+
+        let mut heap = BinaryHeap::new();
+        heap.push(OptionCell::from(Some(3)));
+        heap.push(OptionCell::new());
+        heap.push(OptionCell::from(Some(5)));
+        heap.push(OptionCell::from(Some(1)));
+
+        let popped = std::iter::from_fn(|| heap.pop())
+            .map(OptionCell::into_inner)
+            .collect::<Vec<_>>();
+        assert_eq!(popped, vec![Some(5), Some(3), Some(1), None]);
+    }
+
+    #[test]
+    fn test_get_cloned_or() {
+        let cell = OptionCell::<String>::new();
+        assert_eq!(cell.get_cloned_or("default".to_owned()), "default");
+        assert_eq!(cell.get(), None);
+
+        cell.set("value".to_owned()).unwrap();
+        assert_eq!(cell.get_cloned_or("default".to_owned()), "value");
+        assert_eq!(cell.get().map(String::as_str), Some("value"));
+    }
+
+    #[test]
+    fn test_take_or() {
+        let mut cell = OptionCell::from(Some(42));
+        assert_eq!(cell.take_or(0), 42);
+        assert_eq!(cell.get(), None);
+        assert_eq!(cell.take_or(0), 0);
+        assert_eq!(cell.get(), None);
+    }
+
+    #[test]
+    fn test_take_or_default() {
+        let mut cell = OptionCell::from(Some(42));
+        assert_eq!(cell.take_or_default(), 42);
+        assert_eq!(cell.get(), None);
+        assert_eq!(cell.take_or_default(), 0);
+        assert_eq!(cell.get(), None);
+    }
+
+    #[test]
+    fn test_get_or_init() {
+        let cell = OptionCell::<i32>::new();
+        let value1 = cell.get_or_init(|| 42) as *const i32;
+        let value2 = cell.get_or_init(|| panic!("must not be called")) as *const i32;
+        assert_eq!(value1, value2);
+        assert_eq!(cell.get(), Some(&42));
+    }
+
+    #[test]
+    #[should_panic(expected = "Recursive initialization within get_or_init")]
+    fn test_get_or_init_recursive() {
+        let cell = OptionCell::<i32>::new();
+        cell.get_or_init(|| *cell.get_or_init(|| 42) + 1);
+    }
+
+    #[test]
+    fn test_from_array() {
+        let cells: [OptionCell<i32>; 3] = OptionCell::from_array([Some(1), None, Some(3)]);
+        assert_eq!(cells[0].get(), Some(&1));
+        assert_eq!(cells[1].get(), None);
+        assert_eq!(cells[2].get(), Some(&3));
+        assert!(cells[1].set(2).is_ok());
+        assert_eq!(
+            cells.map(OptionCell::into_inner),
+            [Some(1), Some(2), Some(3)]
+        );
+    }
+
+    #[test]
+    fn test_map_ref() {
+        let cell = OptionCell::<String>::new();
+        assert_eq!(cell.map_ref(String::len), None);
+        cell.set("hello".to_owned()).unwrap();
+        assert_eq!(cell.map_ref(String::len), Some(5));
+    }
+
+    #[test]
+    fn test_get_or_try_init_checked() {
+        let cell = OptionCell::<i32>::new();
+        assert_eq!(cell.get_or_try_init_checked(|| Ok::<_, ()>(42)), Ok(&42));
+        assert_eq!(
+            cell.get_or_try_init_checked(|| -> Result<i32, ()> { panic!("must not be called") }),
+            Ok(&42)
+        );
+    }
+
+    #[test]
+    fn test_get_or_try_init_checked_closure_error() {
+        let cell = OptionCell::<i32>::new();
+        assert_eq!(
+            cell.get_or_try_init_checked(|| Err("failed")),
+            Err(InitError::Closure("failed"))
+        );
+        assert_eq!(cell.get(), None);
+        assert_eq!(cell.get_or_try_init_checked(|| Ok::<_, ()>(42)), Ok(&42));
+    }
+
+    #[test]
+    fn test_get_or_try_init_checked_recursion() {
+        let cell = OptionCell::<i32>::new();
+        let result = cell.get_or_try_init_checked(|| {
+            assert_eq!(
+                cell.get_or_try_init_checked(|| Ok::<_, ()>(1)),
+                Err(InitError::Recursion)
+            );
+            Ok::<_, ()>(42)
+        });
+        assert_eq!(result, Ok(&42));
+
+        let cell = OptionCell::<i32>::new();
+        let result = cell.get_or_try_init_checked(|| {
+            cell.set(1).unwrap();
+            Ok::<_, ()>(42)
+        });
+        assert_eq!(result, Err(InitError::Recursion));
+        assert_eq!(cell.get(), Some(&1));
+    }
+
+    #[test]
+    fn test_from_mut_slice_array() {
+        let mut opts = [None, Some(1), None];
+        let cells: &[OptionCell<i32>] = OptionCell::from_mut_slice(&mut opts[..]);
+        for (i, cell) in cells.iter().enumerate() {
+            let _ = cell.set(i as i32 * 10);
+        }
+        assert_eq!(opts, [Some(0), Some(1), Some(20)]);
+    }
+
+    #[test]
+    fn test_reborrow() {
+        fn fill<T: Clone>(cells: &mut [OptionCell<T>], value: T) {
+            for cell in cells {
+                let _ = cell.set(value.clone());
+            }
+        }
+        fn count<T>(cells: &[OptionCell<T>]) -> usize {
+            cells.iter().filter(|cell| cell.get().is_some()).count()
+        }
+
+        let mut opts = vec![None, Some(1), None];
+        let cells: &mut [OptionCell<i32>] = OptionCell::from_mut_slice(&mut opts);
+        fill(OptionCell::reborrow_mut(cells), 0);
+        assert_eq!(count(OptionCell::reborrow(cells)), 3);
+        cells[0].take();
+        assert_eq!(opts, vec![None, Some(1), Some(0)]);
+    }
+
+    #[test]
+    fn test_init_in_order() {
+        // Diamond: 0 <- 1, 0 <- 2, (1, 2) <- 3
+        let cells: Vec<OptionCell<i32>> = (0..4).map(|_| OptionCell::new()).collect();
+        let mut visited = Vec::new();
+        OptionCell::init_in_order(&cells, &[0, 2, 1, 3, 0], |i, cells| {
+            visited.push(i);
+            let get = |j: usize| *cells[j].get().unwrap();
+            match i {
+                0 => 1,
+                1 => get(0) + 10,
+                2 => get(0) + 100,
+                3 => get(1) + get(2),
+                _ => unreachable!(),
+            }
+        });
+        assert_eq!(visited, vec![0, 2, 1, 3]);
+        assert_eq!(
+            OptionCell::into_vec(cells),
+            vec![Some(1), Some(11), Some(101), Some(112)]
+        );
+    }
+
+    #[test]
+    fn test_upgrade() {
+        struct Node {
+            value: i32,
+            next: OptionCell<Rc<Node>>,
+            prev: OptionCell<Weak<Node>>,
+        }
+        let new_node = |value| {
+            Rc::new(Node {
+                value,
+                next: OptionCell::new(),
+                prev: OptionCell::new(),
+            })
+        };
+
+        let a = new_node(1);
+        let b = new_node(2);
+        assert!(b.prev.upgrade().is_none());
+        b.prev.set(Rc::downgrade(&a)).unwrap();
+        a.next.set(b.clone()).ok().unwrap();
+        // Close the cycle with a weak pointer so that the nodes are not leaked.
+        a.prev.set(Rc::downgrade(&b)).unwrap();
+
+        assert_eq!(b.prev.upgrade().map(|node| node.value), Some(1));
+        assert_eq!(a.prev.upgrade().map(|node| node.value), Some(2));
+        assert_eq!(a.prev.upgrade().unwrap().prev.upgrade().unwrap().value, 1);
+        assert_eq!(a.next.get().unwrap().value, 2);
+
+        let weak = OptionCell::from(Some(Rc::downgrade(&new_node(3))));
+        assert!(weak.upgrade().is_none());
+    }
+
+    #[test]
+    fn test_set_returning() {
+        let cell = OptionCell::<String>::new();
+        match cell.set_returning("first".to_owned()) {
+            SetOutcome::Inserted(value) => assert_eq!(value, "first"),
+            SetOutcome::Rejected(_) => panic!("unexpected rejection"),
+        }
+        match cell.set_returning("second".to_owned()) {
+            SetOutcome::Inserted(_) => panic!("unexpected insertion"),
+            SetOutcome::Rejected(value) => assert_eq!(value, "second"),
+        }
+        assert_eq!(cell.get().map(String::as_str), Some("first"));
+    }
+
+    #[test]
+    fn test_get_mut_or_default() {
+        let mut cell = OptionCell::<Vec<i32>>::new();
+        cell.get_mut_or_default().push(1);
+        cell.get_mut_or_default().push(2);
+        assert_eq!(cell.get(), Some(&vec![1, 2]));
+    }
+
+    #[test]
+    fn test_reader() {
+        let cell = OptionCell::<i32>::new();
+        let reader = cell.reader();
+        assert!(!reader.is_set());
+        assert_eq!(reader.get(), None);
+
+        cell.set(42).unwrap();
+        assert!(reader.is_set());
+        assert_eq!(reader.get(), Some(&42));
+        assert_eq!(format!("{:?}", reader), "CellReader(Some(42))");
+    }
+
+    #[test]
+    fn test_get_or_clone_into() {
+        let cell = OptionCell::<Vec<i32>>::new();
+        let mut buf = Some(vec![0]);
+        cell.get_or_clone_into(&mut buf);
+        assert_eq!(buf, Some(vec![0]));
+
+        cell.set(vec![1, 2]).unwrap();
+        cell.get_or_clone_into(&mut buf);
+        assert_eq!(buf, Some(vec![1, 2]));
+
+        let mut buf = None;
+        cell.get_or_clone_into(&mut buf);
+        assert_eq!(buf, Some(vec![1, 2]));
+        assert_eq!(cell.get(), Some(&vec![1, 2]));
+    }
+
+    #[test]
+    fn test_set_or_err() {
+        let cell = OptionCell::<i32>::new();
+        assert_eq!(cell.set_or_err(42), Ok(()));
+        assert_eq!(cell.set_or_err(43), Err(OptionCellError::AlreadySet));
+        assert_eq!(cell.get(), Some(&42));
+    }
+
+    #[test]
+    fn test_option_cell_error_display() {
+        assert_eq!(
+            OptionCellError::AlreadySet.to_string(),
+            "cell already populated"
+        );
+        assert_eq!(
+            OptionCellError::Recursion.to_string(),
+            "recursive initialization"
+        );
+        assert_eq!(OptionCellError::Empty.to_string(), "cell empty");
+        assert_eq!(
+            OptionCellError::OutOfBounds.to_string(),
+            "index out of bounds"
+        );
+        assert_eq!(
+            OptionCellError::Poisoned.to_string(),
+            "cell poisoned by a panicking initializer"
+        );
+    }
+
+    #[test]
+    fn test_option_cell_error_from_init_error() {
+        fn init(a: &OptionCell<i32>, b: &OptionCell<i32>) -> Result<i32, OptionCellError> {
+            let value = a.get_or_try_init_checked(|| {
+                b.set_or_err(1)?;
+                b.set_or_err(2)?;
+                Ok::<_, OptionCellError>(3)
+            })?;
+            Ok(*value)
+        }
+        let a = OptionCell::new();
+        let b = OptionCell::new();
+        assert_eq!(init(&a, &b), Err(OptionCellError::AlreadySet));
+        assert_eq!(a.get(), None);
+        assert_eq!(
+            OptionCellError::from(InitError::<OptionCellError>::Recursion),
+            OptionCellError::Recursion
+        );
+    }
+
+    #[test]
+    fn test_set_into() {
+        let cell = OptionCell::<u32>::new();
+        assert_eq!(cell.set_into(5u8), Ok(()));
+        assert_eq!(cell.get(), Some(&5));
+        assert_eq!(cell.set_into(6u8), Err(6u8));
+        assert_eq!(cell.get(), Some(&5));
+    }
+
+    #[test]
+    fn test_eq_cross_type() {
+        let owned = OptionCell::from(Some("hello".to_owned()));
+        let borrowed = OptionCell::from(Some("hello"));
+        assert!(owned == borrowed);
+        assert!(owned != OptionCell::from(Some("world")));
+        assert!(owned != OptionCell::<&str>::new());
+        assert!(OptionCell::<String>::new() == OptionCell::<&str>::new());
+    }
+
+    #[test]
+    #[allow(clippy::mutable_key_type)]
+    fn test_hash_map_key() {
+        use std::collections::HashMap;
+
+        let mut map = HashMap::new();
+        map.insert(OptionCell::from(Some(1)), "one");
+        map.insert(OptionCell::new(), "none");
+        map.insert(OptionCell::from(Some(2)), "two");
+        assert_eq!(map.insert(OptionCell::from(Some(1)), "uno"), Some("one"));
+
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.get(&OptionCell::from(Some(1))), Some(&"uno"));
+        assert_eq!(map.get(&OptionCell::new()), Some(&"none"));
+        assert_eq!(map.get(&OptionCell::from(Some(3))), None);
+    }
+
+    #[test]
+    fn test_get_or_init_mut() {
+        let mut cell = OptionCell::<i32>::new();
+        *cell.get_or_init_mut(|| 42) += 1;
+        *cell.get_or_init_mut(|| panic!("must not be called")) += 1;
+        assert_eq!(cell.get(), Some(&44));
+    }
+
+    #[test]
+    fn test_project() {
+        struct Entry {
+            name: String,
+            id: u32,
+        }
+        let cell = OptionCell::<Entry>::new();
+        assert_eq!(cell.project(|entry| entry.name.as_str()), None);
+        cell.set(Entry {
+            name: "foo".to_owned(),
+            id: 1,
+        })
+        .ok()
+        .unwrap();
+        assert_eq!(cell.project(|entry| entry.name.as_str()), Some("foo"));
+        assert_eq!(cell.project(|entry| &entry.id), Some(&1));
+    }
+
+    #[test]
+    fn test_take_if_eq() {
+        let mut cell = OptionCell::from(Some("foo".to_owned()));
+        assert_eq!(cell.take_if_eq("bar"), None);
+        assert_eq!(cell.get().map(String::as_str), Some("foo"));
+        assert_eq!(cell.take_if_eq("foo"), Some("foo".to_owned()));
+        assert_eq!(cell.get(), None);
+        assert_eq!(cell.take_if_eq("foo"), None);
+        assert_eq!(cell.get(), None);
+    }
+
+    #[test]
+    fn test_compare_and_set() {
+        let cell = OptionCell::<i32>::new();
+        assert_eq!(cell.compare_and_set(Some(&0), 42), Err(42));
+        assert_eq!(cell.compare_and_set(None, 42), Ok(()));
+        assert_eq!(cell.compare_and_set(None, 43), Err(43));
+        assert_eq!(cell.compare_and_set(Some(&0), 43), Err(43));
+        assert_eq!(cell.compare_and_set(Some(&42), 43), Err(43));
+        assert_eq!(cell.get(), Some(&42));
+    }
+
+    #[test]
+    fn test_into_options() {
+        let original = vec![Some(1), None, Some(3)];
+        let cells = original.iter().cloned().map(OptionCell::from).collect();
+        let opts = OptionCell::into_options(cells).collect::<Vec<_>>();
+        assert_eq!(opts, original);
+    }
+
+    #[test]
+    fn test_get_or_init_dyn() {
+        let base = 10;
+        let inits: Vec<Box<dyn FnOnce() -> i32>> = vec![
+            Box::new(|| 1),
+            Box::new(move || base + 2),
+            Box::new(|| panic!("must not be called")),
+        ];
+        let cells = vec![
+            OptionCell::new(),
+            OptionCell::new(),
+            OptionCell::from(Some(3)),
+        ];
+        for (cell, init) in cells.iter().zip(inits) {
+            cell.get_or_init_dyn(init);
+        }
+        assert_eq!(
+            OptionCell::into_vec(cells),
+            vec![Some(1), Some(12), Some(3)]
+        );
+
+        let cell = OptionCell::new();
+        let init: Box<dyn FnOnce() -> i32> = Box::new(|| 42);
+        assert_eq!(*cell.get_or_init(init), 42);
+    }
+
+    #[test]
+    fn test_find_set_by() {
+        let cells = OptionCell::from_array([None, Some(1), Some(2), None, Some(2)]);
+        assert_eq!(OptionCell::find_set_by(&cells, |&x| x == 2), Some(2));
+        assert_eq!(OptionCell::find_set_by(&cells, |&x| x > 0), Some(1));
+        assert_eq!(OptionCell::find_set_by(&cells, |&x| x == 3), None);
+    }
+
+    #[test]
+    fn test_from_mut_deref() {
+        let mut boxed = Box::new(None);
+        {
+            let cell = OptionCell::from_mut(&mut *boxed);
+            let cell_ref1 = &*cell;
+            let cell_ref2 = &*cell;
+            assert!(cell_ref1.set(42).is_ok());
+            assert_eq!(cell_ref2.get(), Some(&42));
+        }
+        assert_eq!(*boxed, Some(42));
+
+        let mutex = std::sync::Mutex::new(None);
+        {
+            let mut guard = mutex.lock().unwrap();
+            let cell = OptionCell::from_mut(&mut *guard);
+            assert_eq!(*cell.get_or_init(|| 43), 43);
+        }
+        assert_eq!(mutex.into_inner().unwrap(), Some(43));
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let mut cell = OptionCell::<i32>::new();
+        let empty = cell.snapshot();
+        cell.set(42).unwrap();
+        let populated = cell.snapshot();
+        *cell.get_mut() = Some(43);
+        assert_eq!(empty.get(), None);
+        assert_eq!(populated.get(), Some(&42));
+        assert_eq!(cell.get(), Some(&43));
+        assert!(empty.set(44).is_ok());
+        assert_eq!(cell.get(), Some(&43));
+    }
+
+    #[test]
+    fn test_zst() {
+        use std::mem::size_of;
+        assert_eq!(size_of::<OptionCell<()>>(), size_of::<Option<()>>());
+        assert_eq!(size_of::<OptionCell<()>>(), 1);
+
+        let cell = OptionCell::<()>::new();
+        assert_eq!(cell.get(), None);
+        assert_eq!(cell.set(()), Ok(()));
+        assert_eq!(cell.get(), Some(&()));
+        assert_eq!(cell.set(()), Err(()));
+
+        let mut opts = vec![None, Some(()), None];
+        let cells = OptionCell::from_mut_slice(&mut opts);
+        assert_eq!(cells.len(), 3);
+        assert!(cells[0].set(()).is_ok());
+        assert!(cells[1].set(()).is_err());
+        assert_eq!(opts, vec![Some(()), Some(()), None]);
+
+        let cells = opts.into_iter().map(OptionCell::from).collect();
+        assert_eq!(OptionCell::into_vec(cells), vec![Some(()), Some(()), None]);
+    }
+
+    #[test]
+    fn test_get_or_default() {
+        use std::cell::Cell;
+
+        thread_local! {
+            static COUNT: Cell<usize> = const { Cell::new(0) };
+        }
+        struct Counted;
+        impl Default for Counted {
+            fn default() -> Self {
+                COUNT.with(|count| count.set(count.get() + 1));
+                Counted
+            }
+        }
+
+        let cell = OptionCell::<Counted>::new();
+        let value1 = cell.get_or_default() as *const Counted;
+        let value2 = cell.get_or_default() as *const Counted;
+        assert_eq!(value1, value2);
+        assert_eq!(COUNT.with(Cell::get), 1);
+    }
+
+    #[test]
+    fn test_replace_with() {
+        let increment = |old: Option<&i32>| Some(old.map_or(0, |x| x + 1));
+
+        let mut cell = OptionCell::<i32>::new();
+        assert_eq!(cell.replace_with(increment), None);
+        assert_eq!(cell.get(), Some(&0));
+        assert_eq!(cell.replace_with(increment), Some(0));
+        assert_eq!(cell.get(), Some(&1));
+        assert_eq!(cell.replace_with(|_| None), Some(1));
+        assert_eq!(cell.get(), None);
+    }
+
+    #[test]
+    fn test_view_all() {
+        let cells = OptionCell::from_array([Some(1), None, Some(3)]);
+        let view = OptionCell::view_all(&cells);
+        assert_eq!(view.len(), 3);
+        assert_eq!(view, vec![Some(&1), None, Some(&3)]);
+        assert_eq!(OptionCell::<i32>::view_all(&[]), vec![]);
+    }
+
+    #[test]
+    fn test_from_bytes_mut() {
+        use std::num::NonZeroU32;
+
+        let mut words = [0u32; 4];
+        // Safety: u32 has no padding and any byte pattern is valid.
+        let bytes = unsafe { std::slice::from_raw_parts_mut(words.as_mut_ptr() as *mut u8, 16) };
+        bytes[4..8].copy_from_slice(&1u32.to_ne_bytes());
+        bytes[12..16].copy_from_slice(&3u32.to_ne_bytes());
+
+        // Safety: Option<NonZeroU32> is guaranteed to have the same layout as u32.
+        let cells = unsafe { OptionCell::<NonZeroU32>::from_bytes_mut(bytes) };
+        assert_eq!(cells.len(), 4);
+        assert_eq!(cells[0].get(), None);
+        assert_eq!(cells[1].get().map(|x| x.get()), Some(1));
+        assert_eq!(cells[3].get().map(|x| x.get()), Some(3));
+        cells[2].set(NonZeroU32::new(2).unwrap()).unwrap();
+
+        // Safety: same as above.
+        let cells = unsafe { OptionCell::<NonZeroU32>::from_bytes_mut(&mut bytes[1..]) };
+        assert_eq!(cells.len(), 3);
+        assert_eq!(cells[0].get().map(|x| x.get()), Some(1));
+        assert_eq!(words, [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_get_raw() {
+        let cell = OptionCell::<String>::new();
+        assert!(cell.get_raw().is_none());
+        cell.set("hello".to_owned()).unwrap();
+        let ptr = cell.get_raw().unwrap();
+        assert!(cell.set("world".to_owned()).is_err());
+        // Safety: the cell is still alive and populated.
+        assert_eq!(unsafe { &*ptr }, "hello");
+        assert_eq!(cell.get_raw(), Some(ptr));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_debug_compact() {
+        struct NonDebug;
+        let cell = OptionCell::<NonDebug>::new();
+        assert_eq!(format!("{:?}", cell.debug_compact()), "OptionCell(empty)");
+        cell.set(NonDebug).ok().unwrap();
+        assert_eq!(format!("{:?}", cell.debug_compact()), "OptionCell(set)");
+
+        let cell = OptionCell::from(Some(vec![0; 1000]));
+        assert_eq!(format!("{:?}", cell.debug_compact()), "OptionCell(set)");
+    }
 
     #[test]
-    fn test_new_get() {
+    fn test_get_or_init_with_neighbors() {
+        let cells = OptionCell::from_array([Some(1), None, Some(3)]);
+        let value = OptionCell::get_or_init_with_neighbors(&cells, 1, |cells| {
+            cells[0].get().unwrap() + cells[2].get().unwrap()
+        });
+        assert_eq!(*value, 4);
+        let value = OptionCell::get_or_init_with_neighbors(&cells, 1, |_| unreachable!());
+        assert_eq!(*value, 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "Recursive initialization within get_or_init")]
+    fn test_get_or_init_with_neighbors_recursive() {
+        let cells = OptionCell::from_array([None, None]);
+        OptionCell::get_or_init_with_neighbors(&cells, 0, |cells| {
+            cells[0].set(1).unwrap();
+            2
+        });
+    }
+
+    #[test]
+    fn test_collect_all() {
+        let cells = OptionCell::from_array([Some(1), Some(2), Some(3)]);
+        let all = OptionCell::collect_all(cells);
+        assert_eq!(all.get(), Some(&vec![1, 2, 3]));
+
+        let mut visited = 0;
+        let cells = OptionCell::from_array([Some(1), None, Some(3)]);
+        let all = OptionCell::collect_all(cells.into_iter().inspect(|_| visited += 1));
+        assert_eq!(all.get(), None);
+        assert_eq!(visited, 2);
+    }
+
+    #[test]
+    fn test_get_or_init_cache_hit() {
+        let cell = OptionCell::<String>::new();
+        let mut calls = 0;
+        for _ in 0..3 {
+            let value = cell.get_or_init(|| {
+                calls += 1;
+                "rendered".to_owned()
+            });
+            assert_eq!(value, "rendered");
+        }
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_with_set() {
+        let cell = OptionCell::new().with_set(42);
+        assert_eq!(cell.get(), Some(&42));
+        let cell = cell.with_set(43);
+        assert_eq!(cell.get(), Some(&42));
+    }
+
+    #[test]
+    fn test_fill_counting() {
+        let cells = OptionCell::from_array([None, Some(1), None, None, Some(4)]);
+        let mut visited = Vec::new();
+        let count = OptionCell::fill_counting(&cells, |i| {
+            visited.push(i);
+            if i == 3 {
+                None
+            } else {
+                Some(i * 10)
+            }
+        });
+        assert_eq!(count, 2);
+        assert_eq!(visited, vec![0, 2, 3]);
+        assert_eq!(
+            cells.map(OptionCell::into_inner),
+            [Some(0), Some(1), Some(20), None, Some(4)]
+        );
+    }
+
+    #[test]
+    fn test_value_eq() {
+        let empty1 = OptionCell::<i32>::new();
+        let empty2 = OptionCell::<i32>::new();
+        let one1 = OptionCell::from(Some(1));
+        let one2 = OptionCell::from(Some(1));
+        let two = OptionCell::from(Some(2));
+
+        assert!(one1.value_eq(&one2));
+        assert!(!one1.value_eq(&two));
+        assert!(!one1.value_eq(&empty1));
+        assert!(!empty1.value_eq(&one1));
+        assert!(!empty1.value_eq(&empty2));
+        assert!(empty1 == empty2);
+    }
+
+    #[test]
+    fn test_get_mut_status() {
+        let mut cell = OptionCell::<i32>::new();
+        let (opt, was_set) = cell.get_mut_status();
+        assert!(!was_set);
+        *opt = Some(42);
+        let (opt, was_set) = cell.get_mut_status();
+        assert!(was_set);
+        *opt = None;
+        assert!(!cell.get_mut_status().1);
+    }
+
+    #[test]
+    fn test_into_values_unwrap() {
+        let cells = vec![OptionCell::from(Some(1)), OptionCell::from(Some(2))];
+        assert_eq!(OptionCell::into_values_unwrap(cells), vec![1, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "cell at index 1 is empty")]
+    fn test_into_values_unwrap_empty() {
+        let cells = vec![
+            OptionCell::from(Some(1)),
+            OptionCell::new(),
+            OptionCell::new(),
+        ];
+        OptionCell::into_values_unwrap(cells);
+    }
+
+    #[test]
+    fn test_get_or_err() {
+        fn double(cell: &OptionCell<i32>) -> Result<i32, OptionCellError> {
+            Ok(cell.get_or_err()? * 2)
+        }
+        let cell = OptionCell::<i32>::new();
+        assert_eq!(cell.get_or_err(), Err(EmptyCell));
+        assert_eq!(EmptyCell.to_string(), "cell empty");
+        assert_eq!(double(&cell), Err(OptionCellError::Empty));
+        cell.set(21).unwrap();
+        assert_eq!(cell.get_or_err(), Ok(&21));
+        assert_eq!(double(&cell), Ok(42));
+    }
+
+    #[test]
+    fn test_map_or() {
+        let cell = OptionCell::<String>::new();
+        assert_eq!(cell.map_or(0, String::len), 0);
+        assert_eq!(cell.map_or_else(|| 1, String::len), 1);
+        cell.set("hello".to_owned()).unwrap();
+        assert_eq!(cell.map_or(0, String::len), 5);
+        assert_eq!(cell.map_or_else(|| unreachable!(), String::len), 5);
+    }
+
+    #[test]
+    fn test_flatten_slice() {
+        let cells = OptionCell::from_array([Some(1), None, Some(3), None]);
+        assert_eq!(OptionCell::flatten_slice(&cells).count(), 2);
+        assert_eq!(
+            OptionCell::flatten_slice(&cells).collect::<Vec<_>>(),
+            vec![&1, &3]
+        );
+    }
+
+    #[test]
+    fn test_set_all_empty() {
+        let cells = OptionCell::from_array([None, Some(1), None, Some(3)]);
+        OptionCell::set_all_empty(&cells, 0);
+        assert_eq!(
+            cells.map(OptionCell::into_inner),
+            [Some(0), Some(1), Some(0), Some(3)]
+        );
+    }
+
+    #[test]
+    fn test_get_or_init_or_cycle_rejected_drop() {
+        use std::cell::Cell;
+
+        struct Probe {
+            id: i32,
+            cell: Weak<OptionCell<Probe>>,
+            observed: Rc<Cell<Option<Option<i32>>>>,
+        }
+        impl Drop for Probe {
+            fn drop(&mut self) {
+                if let Some(cell) = self.cell.upgrade() {
+                    self.observed.set(Some(cell.get().map(|probe| probe.id)));
+                }
+            }
+        }
+
+        let observed = Rc::new(Cell::new(None));
+        let cell = Rc::new(OptionCell::new());
+        let probe = |id| Probe {
+            id,
+            cell: Rc::downgrade(&cell),
+            observed: observed.clone(),
+        };
+        let value = cell.get_or_init_or_cycle(
+            || {
+                cell.get_or_init_or_cycle(|| unreachable!(), || probe(1));
+                probe(2)
+            },
+            || unreachable!(),
+        );
+        assert_eq!(value.id, 1);
+        // The rejected probe observed the populated cell while being dropped.
+        assert_eq!(observed.get(), Some(Some(1)));
+    }
+
+    /// The contents of the cell and whether the thread was panicking, as observed by [DropProbe].
+    type DropObservation = (Option<i32>, bool);
+
+    /// A value that records, when dropped, the contents of the cell and whether the thread is panicking.
+    struct DropProbe {
+        id: i32,
+        cell: Weak<OptionCell<DropProbe>>,
+        observed: Rc<std::cell::Cell<Option<DropObservation>>>,
+    }
+
+    impl Drop for DropProbe {
+        fn drop(&mut self) {
+            if let Some(cell) = self.cell.upgrade() {
+                let contents = cell.get().map(|probe| probe.id);
+                self.observed
+                    .set(Some((contents, std::thread::panicking())));
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_or_init_rejected_drop() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let observed = Rc::new(std::cell::Cell::new(None));
+        let cell = Rc::new(OptionCell::new());
+        let probe = |id| DropProbe {
+            id,
+            cell: Rc::downgrade(&cell),
+            observed: observed.clone(),
+        };
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            cell.get_or_init(|| {
+                assert!(cell.set(probe(1)).is_ok());
+                probe(2)
+            });
+        }));
+        assert!(result.is_err());
+        // The rejected probe observed the populated cell, and was dropped before the panic.
+        assert_eq!(observed.get(), Some((Some(1), false)));
+        assert_eq!(cell.get().map(|probe| probe.id), Some(1));
+    }
+
+    #[test]
+    fn test_get_or_try_init_checked_rejected_drop() {
+        let observed = Rc::new(std::cell::Cell::new(None));
+        let cell = Rc::new(OptionCell::new());
+        let probe = |id| DropProbe {
+            id,
+            cell: Rc::downgrade(&cell),
+            observed: observed.clone(),
+        };
+        let result = cell.get_or_try_init_checked(|| {
+            assert!(cell.set(probe(1)).is_ok());
+            Ok::<_, ()>(probe(2))
+        });
+        assert!(matches!(result, Err(InitError::Recursion)));
+        assert_eq!(observed.get(), Some((Some(1), false)));
+    }
+
+    #[test]
+    fn test_from_pinned_mut_slice() {
+        use std::marker::PhantomPinned;
+
+        struct Pinned {
+            value: i32,
+            _pinned: PhantomPinned,
+        }
+        let pinned = |value| Pinned {
+            value,
+            _pinned: PhantomPinned,
+        };
+
+        let opts: Pin<&mut [Option<Pinned>]> = std::pin::pin!([Some(pinned(1)), None]);
+        let addr = opts.as_ref().get_ref()[0].as_ref().unwrap() as *const Pinned;
+        let cells = OptionCell::from_pinned_mut_slice(opts);
+        let cells: &[OptionCell<Pinned>] = cells.into_ref().get_ref();
+        assert!(cells[1].set(pinned(2)).is_ok());
+        let first = cells[0].get().unwrap();
+        assert_eq!(first.value, 1);
+        assert_eq!(first as *const Pinned, addr);
+        assert_eq!(cells[1].get().map(|x| x.value), Some(2));
+    }
+
+    #[test]
+    fn test_get_non_null() {
         let cell = OptionCell::<i32>::new();
+        assert!(cell.get_non_null().is_none());
+        cell.set(42).unwrap();
+        let ptr = cell.get_non_null().unwrap();
+        // Safety: the cell is still alive and populated.
+        assert_eq!(unsafe { *ptr.as_ref() }, 42);
+        assert_eq!(cell.get_raw(), Some(ptr.as_ptr() as *const i32));
+    }
+
+    #[test]
+    fn test_from_iter() {
+        let cell: OptionCell<i32> = std::iter::empty().collect();
         assert_eq!(cell.get(), None);
+        let cell: OptionCell<i32> = std::iter::once(1).collect();
+        assert_eq!(cell.get(), Some(&1));
+
+        let mut iter = vec![1, 2, 3].into_iter();
+        let cell = OptionCell::from_iter(&mut iter);
+        assert_eq!(cell.get(), Some(&1));
+        assert_eq!(iter.collect::<Vec<_>>(), vec![2, 3]);
     }
 
     #[test]
-    fn test_set_get() {
+    fn test_force_set() {
+        let mut cell = OptionCell::<i32>::new();
+        assert_eq!(cell.force_set(42), None);
+        assert!(cell.set(43).is_err());
+        assert_eq!(cell.force_set(43), Some(42));
+        assert_eq!(cell.get(), Some(&43));
+    }
+
+    #[test]
+    fn test_resolve_chain() {
+        const N: usize = 10_000;
+        let cells: Vec<OptionCell<usize>> = (0..N).map(|_| OptionCell::new()).collect();
+        let value = OptionCell::resolve_chain(&cells, N - 1, |i, cells| {
+            if i == 0 {
+                ChainStep::Value(0)
+            } else {
+                match cells[i - 1].get() {
+                    Some(prev) => ChainStep::Value(prev + 1),
+                    None => ChainStep::Depend(i - 1),
+                }
+            }
+        });
+        assert_eq!(*value, N - 1);
+        assert!(cells
+            .iter()
+            .enumerate()
+            .all(|(i, cell)| cell.get() == Some(&i)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Cyclic dependency at 0")]
+    fn test_resolve_chain_cyclic() {
+        let cells = OptionCell::<i32>::from_array([None, None]);
+        OptionCell::resolve_chain(&cells, 0, |i, _| ChainStep::Depend(1 - i));
+    }
+
+    #[test]
+    fn test_clone_into() {
+        let source = OptionCell::from(Some(vec![1, 2, 3]));
+        let mut target = OptionCell::from(Some(Vec::with_capacity(10)));
+        let ptr = target.get().unwrap().as_ptr();
+        source.clone_into(&mut target);
+        assert_eq!(target.get(), Some(&vec![1, 2, 3]));
+        assert_eq!(target.get().unwrap().as_ptr(), ptr);
+
+        let mut target = OptionCell::new();
+        source.clone_into(&mut target);
+        assert_eq!(target.get(), Some(&vec![1, 2, 3]));
+
+        OptionCell::new().clone_into(&mut target);
+        assert_eq!(target.get(), None);
+
+        target.clone_from(&source);
+        assert_eq!(target.get(), Some(&vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn test_value_unchecked() {
+        let cell = OptionCell::from(Some(42));
+        // Safety: the cell is populated.
+        assert_eq!(unsafe { cell.value_unchecked() }, &42);
+    }
+
+    #[test]
+    fn test_assert_all_set() {
+        let cells = OptionCell::from_array([Some(1), None, Some(3)]);
+        cells[1].set(2).unwrap();
+        let populated = OptionCell::assert_all_set(&cells);
+        assert_eq!(populated.len(), 3);
+        assert!(!populated.is_empty());
+        assert_eq!(populated.get(1), &2);
+        assert_eq!(populated.iter().sum::<i32>(), 6);
+        assert!(cells[0].set(0).is_err());
+        assert_eq!(populated.get(0), &1);
+    }
+
+    #[test]
+    #[should_panic(expected = "cell at index 1 is empty")]
+    fn test_assert_all_set_empty() {
+        let cells = OptionCell::from_array([Some(1), None, None]);
+        OptionCell::assert_all_set(&cells);
+    }
+
+    #[test]
+    fn test_try_set() {
+        let cell = OptionCell::<Rc<i32>>::new();
+        let first = Rc::new(1);
+        let second = Rc::new(2);
+        assert!(cell.try_set(first.clone()));
+        assert!(!cell.try_set(second.clone()));
+        assert_eq!(Rc::strong_count(&first), 2);
+        // The rejected value has been dropped.
+        assert_eq!(Rc::strong_count(&second), 1);
+        assert_eq!(cell.get().map(|x| **x), Some(1));
+    }
+
+    #[test]
+    fn test_hash_matches_option() {
+        use std::collections::hash_map::DefaultHasher;
+
+        fn hash_of<H: Hash>(value: &H) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        for opt in [None, Some(0), Some(42)] {
+            assert_eq!(hash_of(&OptionCell::from(opt)), hash_of(&opt));
+        }
+        let opt = Some("hello".to_owned());
+        assert_eq!(hash_of(&OptionCell::from(opt.clone())), hash_of(&opt));
+        assert_ne!(
+            hash_of(&OptionCell::<u64>::new()),
+            hash_of(&OptionCell::from(Some(0u64)))
+        );
+    }
+
+    #[cfg(feature = "async")]
+    fn block_on<F: Future>(fut: F) -> F::Output {
+        use std::task::{Context, Poll, Waker};
+
+        let mut fut = std::pin::pin!(fut);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = fut.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    #[cfg(feature = "async")]
+    async fn yield_now() {
+        let mut yielded = false;
+        std::future::poll_fn(|cx| {
+            if yielded {
+                std::task::Poll::Ready(())
+            } else {
+                yielded = true;
+                cx.waker().wake_by_ref();
+                std::task::Poll::Pending
+            }
+        })
+        .await
+    }
+
+    #[test]
+    #[cfg(feature = "async")]
+    fn test_get_or_init_async() {
         let cell = OptionCell::<i32>::new();
+        let value = block_on(cell.get_or_init_async(|| async {
+            yield_now().await;
+            42
+        }));
+        assert_eq!(*value, 42);
+        let value = block_on(cell.get_or_init_async(|| async { unreachable!() }));
+        assert_eq!(*value, 42);
+    }
+
+    #[test]
+    fn test_take_shared() {
+        let cell = OptionCell::<String>::new();
         let cell_ref1 = &cell;
         let cell_ref2 = &cell;
-        let cell_ref3 = &cell;
+        // Safety: no references to the contents are alive.
+        assert_eq!(unsafe { cell_ref1.take_shared() }, None);
+        cell_ref2.set("hello".to_owned()).unwrap();
+        assert_eq!(cell_ref1.get().map(String::len), Some(5));
+        // Safety: the reference obtained above is no longer alive.
+        assert_eq!(unsafe { cell_ref2.take_shared() }, Some("hello".to_owned()));
         assert_eq!(cell_ref1.get(), None);
-        cell_ref2.set(42).unwrap();
-        assert_eq!(cell_ref3.get(), Some(&42));
+        assert!(cell_ref1.set("world".to_owned()).is_ok());
     }
 
     #[test]
-    fn test_set_fail_get() {
+    fn test_read() {
+        let cell = OptionCell::<i32>::new();
+        assert!(cell.read().is_none());
+        cell.set(42).unwrap();
+        let guard1 = cell.read().unwrap();
+        let guard2 = cell.read().unwrap();
+        assert_eq!(*guard1, 42);
+        drop(guard1);
+        assert_eq!(*guard2, 42);
+        drop(guard2);
+        // Safety: no references to the contents are alive.
+        assert_eq!(unsafe { cell.take_shared() }, Some(42));
+    }
+
+    #[test]
+    fn test_take_shared_while_read() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let cell = OptionCell::from(Some(42));
+        let guard = cell.read().unwrap();
+        // Safety: the only live borrow is the guard, which is checked.
+        let result = catch_unwind(AssertUnwindSafe(|| unsafe { cell.take_shared() }));
+        assert!(result.is_err());
+        assert_eq!(*guard, 42);
+        drop(guard);
+        // Safety: no references to the contents are alive.
+        assert_eq!(unsafe { cell.take_shared() }, Some(42));
+    }
+
+    #[test]
+    fn test_get_copy() {
+        let cell = OptionCell::<usize>::new();
+        assert_eq!(cell.get_copy(), None);
+        cell.set(3).unwrap();
+        assert_eq!(cell.get_copy(), Some(3));
+        assert_eq!(cell.get_copy(), Some(3));
+        assert_eq!(cell.into_inner(), Some(3));
+    }
+
+    #[test]
+    fn test_contains() {
+        let cell = OptionCell::<i32>::new();
+        assert!(!cell.contains(&42));
+        cell.set(42).unwrap();
+        assert!(cell.contains(&42));
+        assert!(!cell.contains(&43));
+
+        let cell = OptionCell::from(Some("hello".to_owned()));
+        assert!(cell.contains(&"hello"));
+    }
+
+    #[test]
+    fn test_get_or_init_panic() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let cell = OptionCell::<String>::new();
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            cell.get_or_init(|| panic!("init failed"));
+        }));
+        assert!(result.is_err());
+        assert_eq!(cell.get(), None);
+        assert_eq!(cell.get_or_init(|| "hello".to_owned()), "hello");
+    }
+
+    #[test]
+    fn test_set_at() {
+        let mut options = vec![None, Some(1)];
+        let cells = OptionCell::from_mut_slice(&mut options);
+        assert_eq!(OptionCell::set_at(cells, 0, 10), Ok(()));
+        assert_eq!(
+            OptionCell::set_at(cells, 0, 11),
+            Err(SetError::AlreadySet(11))
+        );
+        assert_eq!(
+            OptionCell::set_at(cells, 1, 12),
+            Err(SetError::AlreadySet(12))
+        );
+        assert_eq!(
+            OptionCell::set_at(cells, 2, 13),
+            Err(SetError::OutOfBounds(13))
+        );
+        assert_eq!(SetError::OutOfBounds(13).into_inner(), 13);
+        assert_eq!(options, vec![Some(10), Some(1)]);
+    }
+
+    #[test]
+    fn test_to_option() {
+        let cell = OptionCell::<String>::new();
+        assert_eq!(cell.to_option(), None);
+        cell.set("hello".to_owned()).unwrap();
+        assert_eq!(cell.to_option(), Some("hello".to_owned()));
+        assert_eq!(cell.into_option(), Some("hello".to_owned()));
+        assert_eq!(OptionCell::<String>::new().into_option(), None);
+    }
+
+    #[test]
+    fn test_get_deref() {
+        use std::mem::size_of;
+
+        let cell = OptionCell::<Box<str>>::new();
+        assert_eq!(cell.get_deref(), None);
+        cell.set("hello".into()).unwrap();
+        assert_eq!(cell.get_deref(), Some("hello"));
+
+        let cell = OptionCell::from(Some(Box::new(42)));
+        assert_eq!(cell.get_deref(), Some(&42));
+
+        assert_eq!(size_of::<OptionCell<Box<i32>>>(), size_of::<usize>());
+        assert_eq!(size_of::<OptionCell<Box<str>>>(), size_of::<Box<str>>());
+    }
+
+    #[test]
+    fn test_mark() {
+        let visited = (0..3).map(|_| OptionCell::<()>::new()).collect::<Vec<_>>();
+        assert!(visited[1].mark());
+        assert!(!visited[1].mark());
+        assert!(visited[2].mark());
+        let marked = visited
+            .iter()
+            .map(OptionCell::is_set_const)
+            .collect::<Vec<_>>();
+        assert_eq!(marked, vec![false, true, true]);
+    }
+
+    #[test]
+    fn test_drain_filter_set() {
+        let mut vec = vec![Some(1), None, Some(2), Some(3), None, Some(4)]
+            .into_iter()
+            .map(OptionCell::from)
+            .collect::<Vec<_>>();
+        let removed = OptionCell::drain_filter_set(&mut vec, |&x| x % 2 == 0);
+        assert_eq!(removed, vec![2, 4]);
+        assert_eq!(
+            OptionCell::into_vec(vec),
+            vec![Some(1), None, Some(3), None]
+        );
+    }
+
+    #[test]
+    fn test_as_option_ref() {
         let cell = OptionCell::<i32>::new();
+        let empty = cell.as_option_ref();
         cell.set(42).unwrap();
+        assert_eq!(empty, &None);
+        let populated = cell.as_option_ref();
+        assert_eq!(populated, &Some(42));
+        assert_eq!(
+            populated as *const Option<i32>,
+            &cell as *const OptionCell<i32> as *const Option<i32>
+        );
+    }
+
+    #[test]
+    fn test_fmt_pointer() {
+        let cell = OptionCell::from(Some(42));
+        let other = OptionCell::from(Some(42));
         let cell_ref1 = &cell;
         let cell_ref2 = &cell;
-        assert!(cell_ref1.set(43).is_err());
-        assert_eq!(cell_ref2.get(), Some(&42));
+        assert_eq!(format!("{:p}", *cell_ref1), format!("{:p}", *cell_ref2));
+        assert_eq!(format!("{:p}", cell), format!("{:p}", cell_ref1));
+        assert_ne!(format!("{:p}", cell), format!("{:p}", other));
     }
 
     #[test]
-    fn test_from_mut() {
-        {
-            let mut opt = Some(42);
-            let cell = OptionCell::from_mut(&mut opt);
-            let cell_ref1 = &*cell;
-            let cell_ref2 = &*cell;
-            assert_eq!(cell_ref1.get(), Some(&42));
-            assert!(cell_ref2.set(43).is_err());
-        }
-        {
-            let mut opt = None;
-            let cell = OptionCell::from_mut(&mut opt);
-            assert_eq!(cell.get(), None);
-            assert!(cell.set(43).is_ok());
-            assert_eq!(opt, Some(43));
+    fn test_get_or_try_init_retry() {
+        let cell = OptionCell::<i32>::new();
+        let mut calls = 0;
+        let value = cell.get_or_try_init_retry(2, || {
+            calls += 1;
+            Ok::<_, ()>(42)
+        });
+        assert_eq!(value, Ok(&42));
+        assert_eq!(calls, 1);
+        assert_eq!(cell.get_or_try_init_retry(2, || Err(())), Ok(&42));
+    }
+
+    #[test]
+    fn test_get_or_try_init_retry_third() {
+        let cell = OptionCell::<i32>::new();
+        let mut calls = 0;
+        let value = cell.get_or_try_init_retry(2, || {
+            calls += 1;
+            if calls < 3 {
+                Err(calls)
+            } else {
+                Ok(42)
+            }
+        });
+        assert_eq!(value, Ok(&42));
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn test_get_or_try_init_retry_exhausted() {
+        let cell = OptionCell::<i32>::new();
+        let mut calls = 0;
+        let value = cell.get_or_try_init_retry(2, || {
+            calls += 1;
+            Err::<i32, _>(calls)
+        });
+        assert_eq!(value, Err(3));
+        assert_eq!(cell.get(), None);
+    }
+
+    #[test]
+    fn test_get_cell() {
+        let cells = [OptionCell::new(), OptionCell::from(Some(1))];
+        assert!(OptionCell::get_cell(&cells, 2).is_none());
+        let cell = OptionCell::get_cell(&cells, 0).unwrap();
+        cell.set(10).unwrap();
+        assert_eq!(OptionCell::get_cell(&cells, 1).unwrap().get(), Some(&1));
+        assert_eq!(cells[0].get(), Some(&10));
+    }
+
+    #[test]
+    fn test_value_ptr() {
+        let cell = OptionCell::<String>::new();
+        assert!(cell.value_ptr().is_none());
+        cell.set("hello".to_owned()).unwrap();
+        let ptr = cell.value_ptr().unwrap();
+        assert_eq!(ptr as *const String, cell.get_raw().unwrap());
+        // Safety: no references to the value are alive during the write.
+        unsafe { (*ptr).push_str(", world") };
+        assert_eq!(cell.get().map(String::as_str), Some("hello, world"));
+    }
+
+    #[test]
+    fn test_init_logged() {
+        let mut options = vec![None, Some(1), None, None, Some(4)];
+        let cells = OptionCell::from_mut_slice(&mut options);
+        let mut log = vec![100];
+        OptionCell::init_logged(cells, |i| (i != 3).then_some(i * 10), &mut log);
+        assert_eq!(log, vec![100, 0, 2]);
+        assert_eq!(options, vec![Some(0), Some(1), Some(20), None, Some(4)]);
+    }
+
+    #[test]
+    fn test_try_into_value() {
+        assert_eq!(OptionCell::from(Some(42)).try_into_value(), Ok(42));
+        let cell = OptionCell::<i32>::new().try_into_value().unwrap_err();
+        assert_eq!(cell.get(), None);
+    }
+
+    #[test]
+    fn test_diff_slices() {
+        let a = [Some(1), None, Some(3), None, Some(5)].map(OptionCell::from);
+        let b = [Some(1), Some(2), Some(4), None, Some(5)].map(OptionCell::from);
+        assert_eq!(OptionCell::diff_slices(&a, &b), vec![1, 2]);
+        assert_eq!(OptionCell::diff_slices(&a, &a), Vec::<usize>::new());
+    }
+
+    #[test]
+    #[should_panic = "slices have different lengths"]
+    fn test_diff_slices_length_mismatch() {
+        let a = [OptionCell::from(Some(1))];
+        OptionCell::diff_slices(&a, &[]);
+    }
+
+    #[test]
+    fn test_set_at_question_mark() {
+        fn set_pair(cells: &[OptionCell<i32>], i: usize) -> Result<(), OptionCellError> {
+            OptionCell::set_at(cells, i, 1)?;
+            OptionCell::set_at(cells, i + 1, 2)?;
+            Ok(())
         }
+        let cells = [
+            OptionCell::new(),
+            OptionCell::new(),
+            OptionCell::from(Some(3)),
+        ];
+        assert_eq!(set_pair(&cells, 0), Ok(()));
+        assert_eq!(set_pair(&cells, 0), Err(OptionCellError::AlreadySet));
+        assert_eq!(set_pair(&cells, 3), Err(OptionCellError::OutOfBounds));
     }
 
     #[test]
-    fn test_from_mut_slice() {
-        let mut opts = vec![Some(42), None, Some(43)];
-        let cells = OptionCell::from_mut_slice(&mut opts);
-        let cells_ref1 = &*cells;
-        let cells_ref2 = &*cells;
-        let cells_ref3 = &*cells;
-        assert_eq!(cells_ref1.len(), 3);
-        assert_eq!(cells_ref1[0].get(), Some(&42));
-        assert_eq!(cells_ref1[1].get(), None);
-        assert_eq!(cells_ref1[2].get(), Some(&43));
+    fn test_upgrade_unsized() {
+        use std::fmt::Display;
 
-        assert!(cells_ref2[1].set(44).is_ok());
-        assert_eq!(cells_ref3[1].get(), Some(&44));
+        let name: Rc<str> = Rc::from("hello");
+        let cell = OptionCell::<Weak<str>>::new();
+        cell.set(Rc::downgrade(&name)).unwrap();
+        assert_eq!(cell.upgrade().as_deref(), Some("hello"));
+
+        let value: Rc<dyn Display> = Rc::new(42);
+        let cell = OptionCell::from(Some(Rc::downgrade(&value)));
+        assert_eq!(
+            cell.upgrade().map(|value| value.to_string()),
+            Some("42".to_owned())
+        );
+        drop(value);
+        assert!(cell.upgrade().is_none());
+    }
+
+    #[test]
+    #[should_panic = "Recursive initialization within set_into"]
+    fn test_set_into_recursive() {
+        struct Sneaky<'a>(&'a OptionCell<i32>);
+        impl From<Sneaky<'_>> for i32 {
+            fn from(sneaky: Sneaky<'_>) -> Self {
+                sneaky.0.set(1).unwrap();
+                2
+            }
+        }
+
+        let cell = OptionCell::<i32>::new();
+        let _ = cell.set_into(Sneaky(&cell));
     }
 }
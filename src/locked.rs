@@ -0,0 +1,466 @@
+//! A thread-safe variant of [OptionCell](crate::OptionCell) backed by [RwLock].
+
+use std::fmt;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Condvar, Mutex, OnceLock, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::OptionCellError;
+
+/// A thread-safe equivalent of [OptionCell](crate::OptionCell) backed by `RwLock<Option<T>>`.
+///
+/// Unlike OptionCell, the layout is not compatible with `Option<T>`.
+pub struct LockedOptionCell<T> {
+    // Invariant: once populated, the value is never removed while shared.
+    //
+    // The Option<T> is always in a consistent state even if the lock is poisoned,
+    // as it is only ever overwritten as a whole from None to Some(_).
+    inner: RwLock<Option<T>>,
+    // Invariant: if true, the Option<T> is Some(_).
+    //
+    // Checked before taking the lock, so that a rejected `set` neither waits for readers
+    // nor deadlocks against a `LockedRef` held by the same thread.
+    // It may be false for a populated cell, e.g. after `get_mut`, in which case the lock is consulted.
+    populated: AtomicBool,
+    // Notified when the cell is populated. The mutex guards no data;
+    // it only serializes the emptiness check in `wait` with the notification.
+    waiters: Mutex<()>,
+    populated_cond: Condvar,
+}
+
+impl<T> LockedOptionCell<T> {
+    /// Creates a new empty cell.
+    pub const fn new() -> Self {
+        Self {
+            inner: RwLock::new(None),
+            populated: AtomicBool::new(false),
+            waiters: Mutex::new(()),
+            populated_cond: Condvar::new(),
+        }
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, Option<T>> {
+        self.inner.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, Option<T>> {
+        self.inner.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Gets the guard to the underlying value.
+    /// Returns `None` if the cell is empty.
+    ///
+    /// The guard holds the read lock; writers wait until it is dropped.
+    pub fn get(&self) -> Option<LockedRef<'_, T>> {
+        let guard = self.read();
+        if guard.is_some() {
+            self.populated.store(true, Ordering::Release);
+            Some(LockedRef { guard })
+        } else {
+            None
+        }
+    }
+
+    /// Gets the mutable reference to the underlying Option.
+    pub fn get_mut(&mut self) -> &mut Option<T> {
+        // The caller may empty the cell, so the flag is conservatively reset.
+        *self.populated.get_mut() = false;
+        self.inner.get_mut().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn notify_populated(&self) {
+        let _waiters = self.waiters.lock().unwrap_or_else(PoisonError::into_inner);
+        self.populated_cond.notify_all();
+    }
+
+    /// Sets the contents of this cell to `value`.
+    ///
+    /// The value is given back without taking the lock if the cell is known to be populated,
+    /// so calling this method while holding a [LockedRef] to the same cell does not deadlock.
+    ///
+    /// Setting a poisoned empty cell succeeds and clears the poison, as the cell is no longer empty.
+    pub fn set(&self, value: T) -> Result<(), T> {
+        if self.populated.load(Ordering::Acquire) {
+            return Err(value);
+        }
+        {
+            let mut guard = self.write();
+            if guard.is_some() {
+                self.populated.store(true, Ordering::Release);
+                return Err(value);
+            }
+            *guard = Some(value);
+            self.populated.store(true, Ordering::Release);
+        }
+        self.inner.clear_poison();
+        self.notify_populated();
+        Ok(())
+    }
+
+    /// Gets the contents of the cell, initializing with `f` if the cell was empty.
+    ///
+    /// The write lock is held while `f` runs, so concurrent callers wait for the initialization.
+    /// Reentrant initialization of the same cell from `f` deadlocks or panics.
+    ///
+    /// If `f` panics, the cell is left empty and poisoned.
+    /// Subsequent calls on the empty poisoned cell return [PoisonedError]
+    /// until [LockedOptionCell::clear_poison] is called or the cell is populated via [LockedOptionCell::set].
+    /// A populated cell never returns [PoisonedError].
+    pub fn get_or_init<F>(&self, f: F) -> Result<LockedRef<'_, T>, PoisonedError>
+    where
+        F: FnOnce() -> T,
+    {
+        if self.populated.load(Ordering::Acquire) {
+            // The value cannot have been removed, as removal requires exclusive access.
+            return Ok(self.get().unwrap());
+        }
+        {
+            // Declared first so that it is dropped last, after the write guard has poisoned the lock on panic.
+            let _notify = NotifyOnDrop(self);
+            let mut guard = match self.inner.write() {
+                Ok(guard) => guard,
+                // Another thread may have populated the cell since the check above.
+                Err(e) if e.get_ref().is_some() => e.into_inner(),
+                Err(_) => return Err(PoisonedError),
+            };
+            if guard.is_none() {
+                *guard = Some(f());
+            }
+            self.populated.store(true, Ordering::Release);
+        }
+        // The value cannot have been removed, as removal requires exclusive access.
+        Ok(self.get().unwrap())
+    }
+
+    /// Blocks the current thread until the cell is populated, then returns the guard to the value.
+    ///
+    /// Returns [PoisonedError] if the cell is empty and poisoned,
+    /// including when an initializer panics while this method is waiting.
+    /// If the cell is neither populated nor poisoned, this method blocks forever.
+    pub fn wait(&self) -> Result<LockedRef<'_, T>, PoisonedError> {
+        let mut waiters = self.waiters.lock().unwrap_or_else(PoisonError::into_inner);
+        loop {
+            // The checks are done while holding `waiters`, so the notification cannot be missed.
+            if let Some(value) = self.get() {
+                return Ok(value);
+            }
+            if self.is_poisoned() {
+                return Err(PoisonedError);
+            }
+            waiters = self
+                .populated_cond
+                .wait(waiters)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Returns `true` if an initializer panicked while initializing the cell.
+    pub fn is_poisoned(&self) -> bool {
+        self.inner.is_poisoned()
+    }
+
+    /// Clears the poisoned state, allowing the initialization to be retried.
+    pub fn clear_poison(&self) {
+        self.inner.clear_poison();
+    }
+
+    /// Consumes the cell, returning the wrapped `Option<T>`.
+    pub fn into_inner(self) -> Option<T> {
+        self.inner
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Takes the value out of this cell, leaving it empty.
+    pub fn take(&mut self) -> Option<T> {
+        self.get_mut().take()
+    }
+
+    /// Converts a [OnceLock] into a cell, preserving its contents.
+    pub fn from_once_lock(lock: OnceLock<T>) -> Self {
+        LockedOptionCell::from(lock.into_inner())
+    }
+
+    /// Converts the cell into a [OnceLock], preserving its contents.
+    pub fn into_once_lock(self) -> OnceLock<T> {
+        let lock = OnceLock::new();
+        if let Some(value) = self.into_inner() {
+            // The lock is fresh, so it cannot be populated yet.
+            let _ = lock.set(value);
+        }
+        lock
+    }
+}
+
+impl<T> From<Option<T>> for LockedOptionCell<T> {
+    fn from(opt: Option<T>) -> Self {
+        Self {
+            populated: AtomicBool::new(opt.is_some()),
+            inner: RwLock::new(opt),
+            waiters: Mutex::new(()),
+            populated_cond: Condvar::new(),
+        }
+    }
+}
+
+impl<T> Default for LockedOptionCell<T> {
+    fn default() -> Self {
+        LockedOptionCell::new()
+    }
+}
+
+impl<T> From<LockedOptionCell<T>> for Option<T> {
+    fn from(cell: LockedOptionCell<T>) -> Self {
+        cell.into_inner()
+    }
+}
+
+impl<T> From<OnceLock<T>> for LockedOptionCell<T> {
+    fn from(lock: OnceLock<T>) -> Self {
+        LockedOptionCell::from_once_lock(lock)
+    }
+}
+
+impl<T> From<LockedOptionCell<T>> for OnceLock<T> {
+    fn from(cell: LockedOptionCell<T>) -> Self {
+        cell.into_once_lock()
+    }
+}
+
+impl<T> fmt::Debug for LockedOptionCell<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.inner.try_read() {
+            Ok(guard) => f.debug_tuple("LockedOptionCell").field(&*guard).finish(),
+            Err(_) => f
+                .debug_tuple("LockedOptionCell")
+                .field(&"<locked>")
+                .finish(),
+        }
+    }
+}
+
+/// Notifies the waiters of a [LockedOptionCell] when dropped, including on unwinding.
+struct NotifyOnDrop<'a, T>(&'a LockedOptionCell<T>);
+
+impl<T> Drop for NotifyOnDrop<'_, T> {
+    fn drop(&mut self) {
+        self.0.notify_populated();
+    }
+}
+
+/// The error returned by [LockedOptionCell::get_or_init] and [LockedOptionCell::wait] when a previous initializer panicked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PoisonedError;
+
+impl fmt::Display for PoisonedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("cell poisoned by a panicking initializer")
+    }
+}
+
+impl std::error::Error for PoisonedError {}
+
+impl From<PoisonedError> for OptionCellError {
+    fn from(_: PoisonedError) -> Self {
+        OptionCellError::Poisoned
+    }
+}
+
+/// A read guard to the value in a populated [LockedOptionCell].
+pub struct LockedRef<'a, T> {
+    // Invariant: the value is Some(_).
+    guard: RwLockReadGuard<'a, Option<T>>,
+}
+
+impl<T> Deref for LockedRef<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.guard.as_ref().unwrap()
+    }
+}
+
+impl<T> fmt::Debug for LockedRef<'_, T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_set_get() {
+        let cell = LockedOptionCell::<i32>::new();
+        assert!(cell.get().is_none());
+        cell.set(42).unwrap();
+        assert_eq!(cell.set(43), Err(43));
+        assert_eq!(*cell.get().unwrap(), 42);
+        assert_eq!(*cell.get_or_init(|| 43).unwrap(), 42);
+        assert_eq!(cell.into_inner(), Some(42));
+    }
+
+    #[test]
+    fn test_get_or_init() {
+        let cell = LockedOptionCell::<i32>::new();
+        assert_eq!(*cell.get_or_init(|| 42).unwrap(), 42);
+        assert_eq!(
+            *cell.get_or_init(|| panic!("must not be called")).unwrap(),
+            42
+        );
+    }
+
+    #[test]
+    fn test_multithreaded() {
+        let cell = LockedOptionCell::<i32>::new();
+        thread::scope(|s| {
+            let readers = (0..4)
+                .map(|_| {
+                    s.spawn(|| loop {
+                        if let Some(value) = cell.get() {
+                            break *value;
+                        }
+                        thread::yield_now();
+                    })
+                })
+                .collect::<Vec<_>>();
+            s.spawn(|| cell.set(42).unwrap());
+            for reader in readers {
+                assert_eq!(reader.join().unwrap(), 42);
+            }
+        });
+        assert_eq!(*cell.get().unwrap(), 42);
+    }
+
+    #[test]
+    fn test_set_while_borrowed() {
+        let cell = LockedOptionCell::from(Some(1));
+        let value = cell.get().unwrap();
+        assert_eq!(cell.set(2), Err(2));
+        assert_eq!(*value, 1);
+
+        let cell = LockedOptionCell::<i32>::new();
+        let value = cell.get_or_init(|| 1).unwrap();
+        assert_eq!(cell.set(2), Err(2));
+        assert_eq!(*value, 1);
+    }
+
+    #[test]
+    fn test_set_after_get_mut() {
+        let mut cell = LockedOptionCell::from(Some(1));
+        assert_eq!(cell.take(), Some(1));
+        assert_eq!(cell.set(2), Ok(()));
+        *cell.get_mut() = Some(3);
+        assert_eq!(cell.set(4), Err(4));
+        assert_eq!(*cell.get().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_from_once_lock() {
+        let lock = OnceLock::new();
+        lock.set(42).unwrap();
+        let cell = LockedOptionCell::from_once_lock(lock);
+        assert_eq!(*cell.get().unwrap(), 42);
+
+        let cell = LockedOptionCell::<i32>::from(OnceLock::new());
+        assert!(cell.get().is_none());
+        assert!(cell.set(43).is_ok());
+    }
+
+    #[test]
+    fn test_into_once_lock() {
+        let lock = LockedOptionCell::from(Some(42)).into_once_lock();
+        assert_eq!(lock.get(), Some(&42));
+
+        let lock: OnceLock<i32> = LockedOptionCell::new().into();
+        assert_eq!(lock.get(), None);
+    }
+
+    #[test]
+    fn test_poisoned() {
+        use std::panic::catch_unwind;
+
+        let cell = LockedOptionCell::<i32>::new();
+        let result = catch_unwind(|| cell.get_or_init(|| panic!("init failed")).map(|_| ()));
+        assert!(result.is_err());
+        assert!(cell.is_poisoned());
+        assert!(cell.get().is_none());
+        assert_eq!(cell.get_or_init(|| 42).map(|_| ()), Err(PoisonedError));
+
+        fn init(cell: &LockedOptionCell<i32>) -> Result<i32, OptionCellError> {
+            Ok(*cell.get_or_init(|| 42)?)
+        }
+        assert_eq!(init(&cell), Err(OptionCellError::Poisoned));
+
+        cell.clear_poison();
+        assert!(!cell.is_poisoned());
+        assert_eq!(*cell.get_or_init(|| 42).unwrap(), 42);
+    }
+
+    #[test]
+    fn test_poisoned_set() {
+        use std::panic::catch_unwind;
+
+        let mut cell = LockedOptionCell::<i32>::new();
+        let result = catch_unwind(|| cell.get_or_init(|| panic!("init failed")).map(|_| ()));
+        assert!(result.is_err());
+        assert_eq!(cell.set(42), Ok(()));
+        assert!(!cell.is_poisoned());
+        assert_eq!(*cell.get_or_init(|| 43).unwrap(), 42);
+
+        // Poisoned, then populated via get_mut, which does not clear the poison.
+        cell.take();
+        let result = catch_unwind(|| cell.get_or_init(|| panic!("init failed")).map(|_| ()));
+        assert!(result.is_err());
+        *cell.get_mut() = Some(44);
+        assert!(cell.is_poisoned());
+        assert_eq!(*cell.get_or_init(|| 45).unwrap(), 44);
+    }
+
+    #[test]
+    fn test_wait() {
+        use std::time::Duration;
+
+        let cell = LockedOptionCell::<i32>::new();
+        thread::scope(|s| {
+            let consumers = (0..2)
+                .map(|_| s.spawn(|| *cell.wait().unwrap()))
+                .collect::<Vec<_>>();
+            s.spawn(|| {
+                thread::sleep(Duration::from_millis(50));
+                cell.set(42).unwrap();
+            });
+            for consumer in consumers {
+                assert_eq!(consumer.join().unwrap(), 42);
+            }
+        });
+        assert_eq!(*cell.wait().unwrap(), 42);
+    }
+
+    #[test]
+    fn test_wait_poisoned() {
+        use std::panic::catch_unwind;
+        use std::time::Duration;
+
+        let cell = LockedOptionCell::<i32>::new();
+        thread::scope(|s| {
+            let consumer = s.spawn(|| cell.wait().map(|value| *value));
+            s.spawn(|| {
+                thread::sleep(Duration::from_millis(50));
+                let result =
+                    catch_unwind(|| cell.get_or_init(|| panic!("init failed")).map(|_| ()));
+                assert!(result.is_err());
+            });
+            assert_eq!(consumer.join().unwrap(), Err(PoisonedError));
+        });
+        assert_eq!(cell.wait().map(|_| ()), Err(PoisonedError));
+    }
+}
@@ -0,0 +1,59 @@
+//! A global hook observing the population of cells, enabled by the `trace` feature.
+
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+static HOOK: AtomicPtr<()> = AtomicPtr::new(std::ptr::null_mut());
+
+/// Registers the hook invoked on every successful population of a cell through a shared reference,
+/// such as [OptionCell::set](crate::OptionCell::set) or [OptionCell::get_or_init](crate::OptionCell::get_or_init).
+///
+/// The hook receives the address of the cell. Passing `None` unregisters the hook.
+/// Writes through exclusive references, e.g. via [OptionCell::get_mut](crate::OptionCell::get_mut), are not reported.
+pub fn set_hook(hook: Option<fn(*const ())>) {
+    let ptr = match hook {
+        Some(hook) => hook as *mut (),
+        None => std::ptr::null_mut(),
+    };
+    HOOK.store(ptr, Ordering::Release);
+}
+
+pub(crate) fn notify(addr: *const ()) {
+    let ptr = HOOK.load(Ordering::Acquire);
+    if !ptr.is_null() {
+        // Safety: non-null pointers are only stored from `fn(*const ())` in `set_hook`.
+        let hook = unsafe { std::mem::transmute::<*mut (), fn(*const ())>(ptr) };
+        hook(addr);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OptionCell;
+    use std::sync::atomic::AtomicUsize;
+
+    #[test]
+    fn test_hook() {
+        static TARGET: AtomicPtr<()> = AtomicPtr::new(std::ptr::null_mut());
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        fn hook(addr: *const ()) {
+            // Other tests may populate cells concurrently.
+            if std::ptr::eq(addr, TARGET.load(Ordering::SeqCst)) {
+                COUNT.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let mut cells = [OptionCell::<i32>::new(), OptionCell::new()];
+        TARGET.store(&cells[0] as *const _ as *mut (), Ordering::SeqCst);
+        set_hook(Some(hook));
+        cells[0].set(1).unwrap();
+        assert!(cells[0].set(2).is_err());
+        cells[1].set(3).unwrap();
+        *cells[0].get_mut() = None;
+        cells[0].get_or_init(|| 4);
+        set_hook(None);
+        cells[0].take();
+        cells[0].set(5).unwrap();
+        assert_eq!(COUNT.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,73 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use option_cell::OptionCell;
+
+struct CountingAlloc;
+
+thread_local! {
+    // Counted per thread so that the test harness does not interfere.
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+fn count_allocations<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    let after = ALLOCATIONS.with(Cell::get);
+    (result, after - before)
+}
+
+#[test]
+fn test_counting_alloc() {
+    let (_, count) = count_allocations(|| Box::new(42));
+    assert_eq!(count, 1);
+}
+
+#[test]
+fn test_core_operations_do_not_allocate() {
+    let (_, count) = count_allocations(|| {
+        let cell = OptionCell::<u64>::new();
+        assert_eq!(cell.get(), None);
+        cell.set(42).unwrap();
+        assert_eq!(cell.set(43), Err(43));
+        assert_eq!(cell.get(), Some(&42));
+        assert_eq!(*cell.get_or_init(|| 44), 42);
+        OptionCell::<u64>::new().get_or_init(|| 45);
+        let mut cell = cell;
+        assert_eq!(cell.take(), Some(42));
+        assert_eq!(cell.into_inner(), None);
+    });
+    assert_eq!(count, 0);
+}
+
+#[test]
+fn test_slice_conversions_do_not_allocate() {
+    let mut opts = [Some(1), None, Some(3)];
+    let (_, count) = count_allocations(|| {
+        let cells = OptionCell::from_mut_slice(&mut opts);
+        cells[1].set(2).unwrap();
+        let cell = OptionCell::from_mut(cells[0].get_mut());
+        assert_eq!(cell.get(), Some(&1));
+    });
+    assert_eq!(count, 0);
+    assert_eq!(opts, [Some(1), Some(2), Some(3)]);
+}
@@ -0,0 +1,68 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use option_cell::locked::LockedOptionCell;
+use option_cell::OptionCell;
+
+fn assert_send<T: Send>() {}
+fn assert_sync<T: Sync>() {}
+
+// Fails to compile with ambiguity if `T` implements `Sync`.
+trait AmbiguousIfSync<A> {
+    fn some_item() {}
+}
+impl<T: ?Sized> AmbiguousIfSync<()> for T {}
+#[allow(dead_code)]
+struct InvalidSync;
+impl<T: ?Sized + Sync> AmbiguousIfSync<InvalidSync> for T {}
+
+// Fails to compile with ambiguity if `T` implements `Send`.
+trait AmbiguousIfSend<A> {
+    fn some_item() {}
+}
+impl<T: ?Sized> AmbiguousIfSend<()> for T {}
+#[allow(dead_code)]
+struct InvalidSend;
+impl<T: ?Sized + Send> AmbiguousIfSend<InvalidSend> for T {}
+
+#[test]
+fn test_locked_option_cell_send_sync() {
+    assert_send::<LockedOptionCell<i32>>();
+    assert_sync::<LockedOptionCell<i32>>();
+    assert_send::<LockedOptionCell<String>>();
+    assert_sync::<LockedOptionCell<String>>();
+}
+
+#[test]
+fn test_locked_option_cell_requires_send_sync_payload() {
+    <LockedOptionCell<Rc<i32>> as AmbiguousIfSend<_>>::some_item();
+    <LockedOptionCell<Rc<i32>> as AmbiguousIfSync<_>>::some_item();
+    // Cell<i32> is Send but not Sync, so sharing the cell is not allowed.
+    assert_send::<LockedOptionCell<Cell<i32>>>();
+    <LockedOptionCell<Cell<i32>> as AmbiguousIfSync<_>>::some_item();
+}
+
+#[test]
+fn test_option_cell_not_sync() {
+    <OptionCell<i32> as AmbiguousIfSync<_>>::some_item();
+    <OptionCell<String> as AmbiguousIfSync<_>>::some_item();
+    <[OptionCell<i32>] as AmbiguousIfSync<_>>::some_item();
+}
+
+#[test]
+fn test_option_cell_send() {
+    assert_send::<OptionCell<i32>>();
+    assert_send::<OptionCell<String>>();
+    assert_send::<OptionCell<Cell<i32>>>();
+    <OptionCell<Rc<i32>> as AmbiguousIfSend<_>>::some_item();
+
+    let cell = OptionCell::new();
+    cell.set("hello".to_owned()).unwrap();
+    let cell = std::thread::spawn(move || {
+        assert_eq!(cell.get().map(String::as_str), Some("hello"));
+        cell
+    })
+    .join()
+    .unwrap();
+    assert_eq!(cell.into_inner().as_deref(), Some("hello"));
+}